@@ -1,36 +1,61 @@
-
-CREATE TABLE IF NOT EXISTS approvals (
-    "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
-    "evt_block_number" DECIMAL,
-    "approved" VARCHAR(40),
-    "owner" VARCHAR(40),
-    "token_id" DECIMAL
+CREATE TABLE IF NOT EXISTS blocks (
+    "number" BIGINT PRIMARY KEY,
+    "timestamp" TIMESTAMP NOT NULL,
+    "miner_reward" BIGINT NOT NULL,
+    "subsidy" BIGINT NOT NULL,
+    "fees" BIGINT NOT NULL
 );
-CREATE TABLE IF NOT EXISTS approval_for_alls (
-    "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
-    "evt_block_number" DECIMAL,
-    "approved" BOOL,
-    "operator" VARCHAR(40),
-    "owner" VARCHAR(40)
+CREATE TABLE IF NOT EXISTS transactions (
+    "txid" VARCHAR(64) PRIMARY KEY,
+    "block_number" BIGINT NOT NULL,
+    "idx" BIGINT NOT NULL,
+    "amount" BIGINT NOT NULL,
+    "fee" BIGINT NOT NULL,
+    "fee_rate" DOUBLE PRECISION NOT NULL
 );
-CREATE TABLE IF NOT EXISTS ownership_transferreds (
-    "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
-    "evt_block_number" DECIMAL,
-    "new_owner" VARCHAR(40),
-    "previous_owner" VARCHAR(40)
+CREATE TABLE IF NOT EXISTS utxos (
+    -- txid:vout
+    "id" VARCHAR(75) PRIMARY KEY,
+    "transaction" VARCHAR(64) NOT NULL,
+    "address" VARCHAR(100),
+    "amount" BIGINT NOT NULL,
+    -- Comma separated start:size pairs
+    "sat_ranges" TEXT NOT NULL,
+    "spent_in" VARCHAR(64)
 );
-CREATE TABLE IF NOT EXISTS transfers (
-    "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
-    "evt_block_number" DECIMAL,
-    "from" VARCHAR(40),
-    "to" VARCHAR(40),
-    "token_id" DECIMAL
-);
\ No newline at end of file
+CREATE TABLE IF NOT EXISTS inscriptions (
+    -- txid + "i" + index
+    "id" VARCHAR(80) PRIMARY KEY,
+    "number" BIGINT NOT NULL,
+    "sat" BIGINT,
+    "rarity" VARCHAR(20) NOT NULL,
+    "cursed" BOOL NOT NULL,
+    "vindicated" BOOL NOT NULL,
+    "curse" VARCHAR(40) NOT NULL,
+    "content_type" TEXT,
+    "content_encoding" TEXT,
+    "content_length" BIGINT NOT NULL,
+    "metaprotocol" TEXT,
+    "metadata" TEXT,
+    "parent" VARCHAR(80),
+    "pointer" BIGINT,
+    -- Comma separated ids of the inscriptions previously made on the same sat
+    "reinscription_of" TEXT NOT NULL,
+    "genesis_transaction" VARCHAR(64) NOT NULL,
+    "genesis_block" BIGINT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS inscription_transfers (
+    -- txid:inscription id
+    "id" VARCHAR(150) PRIMARY KEY,
+    "inscription_id" VARCHAR(80) NOT NULL,
+    "from_outpoint" VARCHAR(75) NOT NULL,
+    "to_outpoint" VARCHAR(75) NOT NULL,
+    "offset" BIGINT NOT NULL,
+    "from_address" VARCHAR(100),
+    "to_address" VARCHAR(100),
+    "txid" VARCHAR(64) NOT NULL,
+    "block_number" BIGINT NOT NULL,
+    "state" VARCHAR(40) NOT NULL
+);
+CREATE INDEX IF NOT EXISTS inscription_transfers_inscription_id_idx ON inscription_transfers ("inscription_id");
+CREATE INDEX IF NOT EXISTS inscriptions_sat_idx ON inscriptions ("sat");
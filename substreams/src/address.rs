@@ -1,30 +1,36 @@
 use bitcoin::{
     blockdata::script::Script,
-    network::Network,
     address::Address,
     // hash160::Hash
 };
 
-pub fn address_from_scriptpubkey(script_pub_key_hex: &str) -> Option<String> {
-    // Decode the script from hex
-    let hex_data = hex::decode(script_pub_key_hex).expect("Valid hex script");
-    let script = Script::from_bytes(&hex_data);
+use crate::ord::chain::Chain;
 
+pub fn address_from_script(script: &Script, chain: Chain) -> Option<String> {
     // Create a Bitcoin address from the public key script
-    Address::from_script(script, Network::Bitcoin)
+    Address::from_script(script, chain.network())
         .map(|address| address.to_string())
         .ok()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::address_from_scriptpubkey;
+    use bitcoin::ScriptBuf;
+
+    use super::address_from_script;
+    use crate::ord::chain::Chain;
 
     #[test]
-    fn test_address_from_scriptpubkey() {
+    fn test_address_from_script() {
+        let script = ScriptBuf::from_bytes(hex::decode("76a914534e48e9a49ce7ebf8d84c8313e4edfa48852fa188ac").unwrap());
         assert_eq!(
-            address_from_scriptpubkey("76a914534e48e9a49ce7ebf8d84c8313e4edfa48852fa188ac"),
+            address_from_script(&script, Chain::Mainnet),
             Some("18bUsFHLgFotUqAL9ftLBVenJDVP7M64Nu".into())
-        )
+        );
+        assert_eq!(
+            address_from_script(&script, Chain::Testnet),
+            Some("mo7SAJNKVHF9FwdwsEri1Qs7AD661qoCEX".into())
+        );
+        assert_eq!(address_from_script(&ScriptBuf::new(), Chain::Mainnet), None);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,197 @@
+use substreams_database_change::tables::{Row, Tables};
+
+use crate::pb::ordinals::v1::{self as ord_proto, Curse, InscriptionState, InscriptionTransfers, Rarity};
+use crate::sat_ranges::format_sat_ranges;
+
+/// Heights of the blocks whose coinbase transaction has the same txid as the coinbase
+/// of an earlier block, whose outputs it overwrote. BIP30 forbids it from then on.
+const BIP30_DUPLICATE_HEIGHTS: [u64; 2] = [91_842, 91_880];
+
+/// Adds the rows of the `schema.sql` tables created or updated by `block` to `tables`
+pub fn block_changes(tables: &mut Tables, block: &ord_proto::Block, transfers: &InscriptionTransfers) {
+    tables.create_row("blocks", block.number.to_string())
+        .set("timestamp", &prost_types::Timestamp { seconds: block.timestamp as i64, nanos: 0 })
+        .set("miner_reward", block.miner_reward)
+        .set("subsidy", block.subsidy)
+        .set("fees", block.fees);
+
+    for tx in &block.txs {
+        // The rows of a duplicate coinbase replace the ones of the earlier coinbase
+        let duplicate = tx.idx == 0 && BIP30_DUPLICATE_HEIGHTS.contains(&block.number);
+
+        create_row(tables, "transactions", &tx.txid, duplicate)
+            .set("block_number", block.number)
+            .set("idx", tx.idx)
+            .set("amount", tx.amount)
+            .set("fee", tx.fee)
+            .set("fee_rate", tx.fee_rate.to_string());
+
+        // Only one of them is set, depending on whether the transaction is the coinbase
+        for output in tx.coinbase_ordinals.iter().chain(&tx.relative_ordinals) {
+            let row = create_row(tables, "utxos", &output.utxo, duplicate);
+            row.set("transaction", &tx.txid)
+                .set("amount", output.size)
+                .set("sat_ranges", format_sat_ranges(&output.absolute_ordinals));
+            if let Some(address) = &output.address {
+                row.set("address", address);
+            }
+        }
+
+        for utxo in &tx.input_utxos {
+            tables.update_row("utxos", utxo)
+                .set("spent_in", &tx.txid);
+        }
+
+        for inscription in &tx.inscriptions {
+            let row = tables.create_row("inscriptions", &inscription.id);
+            row.set("number", inscription.number)
+                .set("cursed", inscription.cursed)
+                .set("vindicated", inscription.vindicated)
+                .set("curse", Curse::from_i32(inscription.curse).unwrap_or_default().as_str_name())
+                .set("rarity", Rarity::from_i32(inscription.rarity).unwrap_or_default().as_str_name())
+                .set("content_length", &inscription.content_length)
+                .set("reinscription_of", inscription.reinscription_of.join(","))
+                .set("genesis_transaction", &tx.txid)
+                .set("genesis_block", block.number);
+
+            // Content is left out, it can be large and is not always valid text
+            let optional_fields = [
+                ("content_type", &inscription.content_type),
+                ("content_encoding", &inscription.content_encoding),
+                ("metaprotocol", &inscription.metaprotocol),
+                ("metadata", &inscription.metadata),
+                ("parent", &inscription.parent),
+            ];
+            for (name, value) in optional_fields {
+                if let Some(value) = value {
+                    row.set(name, value);
+                }
+            }
+            if let Some(sat) = inscription.sat {
+                row.set("sat", sat);
+            }
+            if let Some(pointer) = inscription.pointer {
+                row.set("pointer", pointer);
+            }
+        }
+    }
+
+    for transfer in &transfers.transfers {
+        let row = tables.create_row("inscription_transfers", format!("{}:{}", transfer.txid, transfer.inscription_id));
+        row.set("inscription_id", &transfer.inscription_id)
+            .set("from_outpoint", &transfer.from_outpoint)
+            .set("to_outpoint", &transfer.to_outpoint)
+            .set("offset", transfer.offset)
+            .set("txid", &transfer.txid)
+            .set("block_number", transfer.block_height)
+            .set("state", InscriptionState::from_i32(transfer.state).unwrap_or_default().as_str_name());
+        if let Some(address) = &transfer.from_address {
+            row.set("from_address", address);
+        }
+        if let Some(address) = &transfer.to_address {
+            row.set("to_address", address);
+        }
+    }
+}
+
+/// Row to create in `table`, or to update if it `replaces` an existing one
+fn create_row<'a>(tables: &'a mut Tables, table: &str, key: &str, replaces: bool) -> &'a mut Row {
+    if replaces {
+        tables.update_row(table, key)
+    } else {
+        tables.create_row(table, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use substreams_database_change::pb::database::{table_change::{Operation, PrimaryKey}, TableChange};
+
+    use super::*;
+    use crate::pb::ordinals::v1::{InscriptionTransfer, OrdinalBlock, SatRange};
+
+    fn field<'a>(change: &'a TableChange, name: &str) -> Option<&'a str> {
+        change.fields.iter()
+            .find(|field| field.name == name)
+            .map(|field| field.new_value.as_str())
+    }
+
+    #[test]
+    fn test_block_changes() {
+        let block = ord_proto::Block {
+            number: 800_000,
+            txs: vec![ord_proto::Transaction {
+                txid: "a".into(),
+                idx: 1,
+                input_utxos: vec!["prev:0".into()],
+                relative_ordinals: vec![OrdinalBlock {
+                    utxo: "a:0".into(),
+                    size: 15,
+                    absolute_ordinals: vec![SatRange { start: 100, size: 10, ..Default::default() }, SatRange { start: 500, size: 5, ..Default::default() }],
+                    ..Default::default()
+                }],
+                inscriptions: vec![ord_proto::Inscription {
+                    id: "ai0".into(),
+                    number: -3,
+                    cursed: true,
+                    curse: Curse::Reinscription as i32,
+                    reinscription_of: vec!["xi0".into(), "yi0".into()],
+                    sat: Some(100),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let transfers = InscriptionTransfers {
+            transfers: vec![InscriptionTransfer {
+                inscription_id: "xi0".into(),
+                txid: "a".into(),
+                state: InscriptionState::Burned as i32,
+                ..Default::default()
+            }],
+        };
+
+        let mut tables = Tables::new();
+        block_changes(&mut tables, &block, &transfers);
+        let changes = tables.to_database_changes().table_changes;
+
+        let change = |table: &str, pk: &str| {
+            changes.iter()
+                .find(|change| change.table == table && change.primary_key == Some(PrimaryKey::Pk(pk.into())))
+                .unwrap()
+        };
+        assert_eq!(changes.len(), 6);
+        assert_eq!(change("utxos", "a:0").operation, Operation::Create as i32);
+        assert_eq!(field(change("utxos", "a:0"), "sat_ranges"), Some("100:10,500:5"));
+        assert_eq!(field(change("inscriptions", "ai0"), "curse"), Some("CURSE_REINSCRIPTION"));
+        assert_eq!(field(change("inscriptions", "ai0"), "reinscription_of"), Some("xi0,yi0"));
+        assert_eq!(field(change("inscriptions", "ai0"), "sat"), Some("100"));
+        assert_eq!(field(change("inscription_transfers", "a:xi0"), "state"), Some("INSCRIPTION_STATE_BURNED"));
+
+        // Spent UTXO
+        assert_eq!(change("utxos", "prev:0").operation, Operation::Update as i32);
+        assert_eq!(field(change("utxos", "prev:0"), "spent_in"), Some("a"));
+    }
+
+    #[test]
+    fn test_bip30_duplicate_coinbase() {
+        let coinbase = ord_proto::Transaction {
+            txid: "cb".into(),
+            coinbase_ordinals: vec![OrdinalBlock { utxo: "cb:0".into(), size: 50, ..Default::default() }],
+            ..Default::default()
+        };
+        let operations = |number: u64| {
+            let block = ord_proto::Block { number, txs: vec![coinbase.clone()], ..Default::default() };
+            let mut tables = Tables::new();
+            block_changes(&mut tables, &block, &InscriptionTransfers::default());
+            tables.to_database_changes().table_changes.iter()
+                .filter(|change| change.table != "blocks")
+                .map(|change| change.operation)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(operations(91_812), vec![Operation::Create as i32; 2]);
+        assert_eq!(operations(91_842), vec![Operation::Update as i32; 2]);
+    }
+}
@@ -0,0 +1,481 @@
+use bitcoin::{absolute::LockTime, consensus::{deserialize, serialize}, transaction::Version};
+
+use crate::address::address_from_script;
+use crate::error::{Diagnostics, Error};
+use crate::ord::chain::Chain;
+use crate::pb::ordinals::v1 as ord_proto;
+use crate::pb::sf::bitcoin::r#type::v1 as btc;
+use crate::sat_ranges::utxo_id;
+use crate::sats_utils::btc_to_sats;
+
+/// Transaction decoded once from its raw hex. Values, scripts, outpoints and
+/// envelopes are all taken from the decoded transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedTransaction {
+    pub txid: String,
+    /// Index of the transaction in its block, undecodable transactions included
+    pub idx: u32,
+    pub vsize: i32,
+    pub raw: bitcoin::Transaction,
+}
+
+impl DecodedTransaction {
+    /// Decodes the raw hex of `tx`. The BTC amounts of its outputs are floats, they
+    /// are only checked against the values of the raw outputs.
+    pub fn decode(tx: &btc::Transaction) -> Result<Self, Error> {
+        let bytes = hex::decode(&tx.hex)
+            .map_err(|source| Error::InvalidHex { txid: tx.txid.clone(), source })?;
+        let raw: bitcoin::Transaction = deserialize(&bytes)
+            .map_err(|source| Error::InvalidTransaction { txid: tx.txid.clone(), source })?;
+
+        for (n, output) in raw.output.iter().enumerate() {
+            let value = output.value.to_sat();
+            let btc_amount = tx.vout.get(n).map(|vout| vout.value);
+            if btc_amount.and_then(btc_to_sats) != Some(value) {
+                substreams::log::info!(
+                    "Output {}:{} holds {} sats but its amount is {:?} BTC",
+                    tx.txid, n, value, btc_amount,
+                );
+            }
+        }
+
+        Ok(Self {
+            txid: tx.txid.clone(),
+            idx: 0,
+            vsize: tx.vsize,
+            raw,
+        })
+    }
+
+    /// UTXOs spent by the transaction
+    pub fn inputs(&self) -> impl Iterator<Item = String> + '_ {
+        self.raw.input.iter().map(|input| input.previous_output.to_string())
+    }
+
+    /// UTXO created by output `vout`
+    pub fn utxo(&self, vout: usize) -> String {
+        utxo_id(&self.txid, vout as u32)
+    }
+
+    /// Values of the outputs, in sats
+    pub fn output_values(&self) -> impl Iterator<Item = u64> + '_ {
+        self.raw.output.iter().map(|output| output.value.to_sat())
+    }
+
+    pub fn amount(&self) -> u64 {
+        self.output_values().sum()
+    }
+
+    pub fn output_address(&self, vout: usize, chain: Chain) -> Option<String> {
+        address_from_script(&self.raw.output[vout].script_pubkey, chain)
+    }
+
+    /// Transaction without any input or output, standing for an undecodable one
+    fn empty(txid: &str) -> Self {
+        Self {
+            txid: txid.to_string(),
+            idx: 0,
+            vsize: 0,
+            raw: bitcoin::Transaction {
+                version: Version::ONE,
+                lock_time: LockTime::ZERO,
+                input: vec![],
+                output: vec![],
+            },
+        }
+    }
+}
+
+/// Block whose transactions are decoded once, to be shared by all the indexing steps
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedBlock {
+    /// Network of the block, set by the `map_decoded_block` module param
+    pub chain: Chain,
+    pub height: u64,
+    pub time: u64,
+    /// Decoded transactions, starting with the coinbase
+    pub txs: Vec<DecodedTransaction>,
+    /// Transactions which could not be decoded
+    pub diagnostics: Diagnostics,
+}
+
+impl DecodedBlock {
+    /// Decodes the transactions of `block`, from `chain`. Undecodable transactions are skipped and
+    /// reported, except for the coinbase which is replaced by a coinbase without any
+    /// output, so that the first transaction of a block is always its coinbase.
+    pub fn decode(block: &btc::Block, chain: Chain) -> Self {
+        let mut diagnostics = Diagnostics::default();
+        let mut txs = vec![];
+
+        for (idx, tx) in block.tx.iter().enumerate() {
+            match DecodedTransaction::decode(tx) {
+                Ok(tx) => txs.push(DecodedTransaction { idx: idx as u32, ..tx }),
+                Err(err) => {
+                    diagnostics.report(err);
+                    if idx == 0 {
+                        txs.push(DecodedTransaction::empty(&tx.txid));
+                    }
+                }
+            }
+        }
+
+        if txs.is_empty() {
+            diagnostics.report(Error::EmptyBlock);
+        }
+
+        Self {
+            chain,
+            height: block.height as u64,
+            time: block.time as u64,
+            txs,
+            diagnostics,
+        }
+    }
+
+    /// Block as output by `map_decoded_block`, its transactions consensus encoded
+    pub fn encode(&self) -> ord_proto::RawBlock {
+        ord_proto::RawBlock {
+            chain: self.chain.to_string(),
+            height: self.height,
+            time: self.time,
+            txs: self.txs.iter()
+                .map(|tx| ord_proto::RawTransaction {
+                    txid: tx.txid.clone(),
+                    idx: tx.idx,
+                    vsize: tx.vsize,
+                    raw: serialize(&tx.raw),
+                })
+                .collect(),
+            diagnostics: self.diagnostics.0.clone(),
+        }
+    }
+
+    /// Decodes a block output by `map_decoded_block`. Its transactions were already
+    /// decoded once, so they are only deserialized, which is much cheaper than
+    /// decoding the source block again. Its chain was parsed from the module param,
+    /// it is valid.
+    pub fn from_raw(block: &ord_proto::RawBlock) -> Self {
+        let mut diagnostics = Diagnostics(block.diagnostics.clone());
+        let mut txs = vec![];
+
+        for tx in &block.txs {
+            match deserialize(&tx.raw) {
+                Ok(raw) => txs.push(DecodedTransaction { txid: tx.txid.clone(), idx: tx.idx, vsize: tx.vsize, raw }),
+                Err(source) => {
+                    diagnostics.report(Error::InvalidTransaction { txid: tx.txid.clone(), source });
+                    if tx.idx == 0 {
+                        txs.push(DecodedTransaction::empty(&tx.txid));
+                    }
+                }
+            }
+        }
+
+        Self {
+            chain: block.chain.parse().unwrap_or_default(),
+            height: block.height,
+            time: block.time,
+            txs,
+            diagnostics,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_utils {
+    use bitcoin::{hashes::Hash, Amount, OutPoint, ScriptBuf, Sequence, TxIn, TxOut, Txid, Witness};
+
+    use super::*;
+
+    /// Txid standing for the transaction named `name`
+    pub fn txid(name: &str) -> Txid {
+        Txid::hash(name.as_bytes())
+    }
+
+    /// UTXO created by output `vout` of the transaction named `name`
+    pub fn utxo(name: &str, vout: u32) -> String {
+        utxo_id(&txid(name).to_string(), vout)
+    }
+
+    /// Transaction named `name` spending `inputs` and creating outputs of `values` sats
+    pub fn tx(name: &str, inputs: &[(&str, u32)], values: &[u64]) -> DecodedTransaction {
+        DecodedTransaction {
+            txid: txid(name).to_string(),
+            idx: 0,
+            vsize: 0,
+            raw: bitcoin::Transaction {
+                version: Version::TWO,
+                lock_time: LockTime::ZERO,
+                input: inputs.iter()
+                    .map(|&(name, vout)| TxIn {
+                        previous_output: OutPoint { txid: txid(name), vout },
+                        script_sig: ScriptBuf::new(),
+                        sequence: Sequence::MAX,
+                        witness: Witness::new(),
+                    })
+                    .collect(),
+                output: values.iter()
+                    .map(|&value| TxOut { value: Amount::from_sat(value), script_pubkey: ScriptBuf::new() })
+                    .collect(),
+            },
+        }
+    }
+
+    /// Transaction whose txid is `txid` as is, with `outputs` outputs and no input. The
+    /// UTXOs it spends and creates are only known from the sat ranges it is given.
+    pub fn bare_tx(txid: &str, outputs: usize) -> DecodedTransaction {
+        DecodedTransaction {
+            txid: txid.into(),
+            ..tx(txid, &[], &vec![0; outputs])
+        }
+    }
+
+    /// Block at `height` made of `txs`, in order
+    pub fn block(height: u64, txs: Vec<DecodedTransaction>) -> DecodedBlock {
+        DecodedBlock {
+            chain: Chain::Mainnet,
+            height,
+            time: 0,
+            txs: txs.into_iter()
+                .enumerate()
+                .map(|(idx, tx)| DecodedTransaction { idx: idx as u32, ..tx })
+                .collect(),
+            diagnostics: Diagnostics::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use bitcoin::{
+        block::{self, Header}, blockdata::script, hashes::Hash, BlockHash, CompactTarget, TxMerkleNode, Witness,
+    };
+    use prost::Message;
+
+    use super::test_utils::*;
+    use super::*;
+    use crate::ord::inscription::Inscription as Payload;
+    use crate::pb::ordinals::v1::{DiagnosticKind, SatRange, SatRanges};
+    use crate::sat_ranges::{assign_sat_ranges, BlockSatRanges};
+
+    fn encode(tx: &DecodedTransaction) -> btc::Transaction {
+        btc::Transaction {
+            txid: tx.txid.clone(),
+            hex: hex::encode(serialize(&tx.raw)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let decoded = tx("a", &[("prev", 1)], &[1_000, 2_000]);
+        let tx = btc::Transaction {
+            txid: decoded.txid.clone(),
+            hex: hex::encode(serialize(&decoded.raw)),
+            vin: vec![btc::Vin { txid: txid("prev").to_string(), vout: 1, ..Default::default() }],
+            vout: vec![
+                btc::Vout { value: 0.00001, n: 0, ..Default::default() },
+                // Inconsistent amounts are only logged
+                btc::Vout { value: 1., n: 1, ..Default::default() },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(DecodedTransaction::decode(&tx).unwrap(), decoded);
+        assert_eq!(decoded.inputs().collect::<Vec<_>>(), vec![utxo("prev", 1)]);
+        assert_eq!(decoded.utxo(1), utxo("a", 1));
+        assert_eq!(decoded.amount(), 3_000);
+
+        assert!(matches!(
+            DecodedTransaction::decode(&btc::Transaction { hex: "zz".into(), ..Default::default() }),
+            Err(Error::InvalidHex { .. }),
+        ));
+        assert!(matches!(
+            DecodedTransaction::decode(&btc::Transaction { hex: "00".into(), ..Default::default() }),
+            Err(Error::InvalidTransaction { .. }),
+        ));
+    }
+
+    #[test]
+    fn test_decode_malformed_block() {
+        let invalid = |txid: &str| btc::Transaction { txid: txid.into(), hex: "00".into(), ..Default::default() };
+        let valid = tx("a", &[("prev", 0)], &[1_000]);
+
+        // Undecodable transactions are skipped, except for the coinbase
+        let block = DecodedBlock::decode(&btc::Block {
+            height: 1,
+            tx: vec![invalid("cb"), encode(&valid), invalid("b")],
+            ..Default::default()
+        }, Chain::Testnet);
+        assert_eq!(block.txs, vec![DecodedTransaction::empty("cb"), DecodedTransaction { idx: 1, ..valid }]);
+        assert_eq!(
+            block.diagnostics.0.iter()
+                .map(|diagnostic| (diagnostic.kind, diagnostic.txid.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (DiagnosticKind::InvalidTransaction as i32, "cb"),
+                (DiagnosticKind::InvalidTransaction as i32, "b"),
+            ],
+        );
+
+        // Shared as is through `map_decoded_block`, chain and diagnostics included
+        assert_eq!(DecodedBlock::from_raw(&block.encode()), block);
+
+        let block = DecodedBlock::decode(&btc::Block { height: 1, ..Default::default() }, Chain::Mainnet);
+        assert!(block.txs.is_empty());
+        assert_eq!(block.diagnostics.0[0].kind, DiagnosticKind::EmptyBlock as i32);
+    }
+
+    /// Source block of `block` at `height`, its transactions filled in like the firehose does
+    fn source_block(block: &bitcoin::Block, height: u64) -> btc::Block {
+        btc::Block {
+            hash: block.block_hash().to_string(),
+            height: height as i64,
+            time: block.header.time as i64,
+            tx: block.txdata.iter()
+                .map(|tx| btc::Transaction {
+                    hex: hex::encode(serialize(tx)),
+                    txid: tx.txid().to_string(),
+                    hash: tx.wtxid().to_string(),
+                    size: tx.total_size() as i32,
+                    vsize: tx.vsize() as i32,
+                    weight: tx.weight().to_wu() as i32,
+                    version: tx.version.0 as u32,
+                    locktime: tx.lock_time.to_consensus_u32(),
+                    vin: tx.input.iter()
+                        .map(|input| btc::Vin {
+                            txid: input.previous_output.txid.to_string(),
+                            vout: input.previous_output.vout,
+                            script_sig: Some(btc::ScriptSig {
+                                asm: input.script_sig.to_asm_string(),
+                                hex: hex::encode(input.script_sig.as_bytes()),
+                            }),
+                            sequence: input.sequence.0,
+                            txinwitness: input.witness.iter().map(hex::encode).collect(),
+                            ..Default::default()
+                        })
+                        .collect(),
+                    vout: tx.output.iter()
+                        .enumerate()
+                        .map(|(n, output)| btc::Vout {
+                            value: output.value.to_btc(),
+                            n: n as u32,
+                            script_pub_key: Some(btc::ScriptPubKey {
+                                asm: output.script_pubkey.to_asm_string(),
+                                hex: hex::encode(output.script_pubkey.as_bytes()),
+                                address: address_from_script(&output.script_pubkey, Chain::Mainnet).unwrap_or_default(),
+                                ..Default::default()
+                            }),
+                        })
+                        .collect(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Block of `reveals` transactions each revealing an inscription, like the blocks
+    /// of the BRC-20 mints, spending the UTXOs of its commit transaction
+    fn heavy_inscription_block(reveals: usize) -> btc::Block {
+        let coinbase = tx("cb", &[], &[625_000_000]);
+        let reveals = (0..reveals).map(|n| {
+            let mut reveal = tx(&format!("reveal {}", n), &[(&format!("commit {}", n), 0)], &[546]);
+            let body = format!(r#"{{"p":"brc-20","op":"mint","tick":"ordi","amt":"1000","n":{}}}"#, n);
+            let payload = Payload::new(Some(b"text/plain;charset=utf-8".to_vec()), Some(body.into_bytes()));
+            let script = Payload::append_batch_reveal_script(&[payload], script::Builder::new());
+            reveal.raw.input[0].witness = Witness::from_slice(&[script.into_bytes(), vec![0xc0; 33]]);
+            reveal
+        });
+
+        let block = bitcoin::Block {
+            header: Header {
+                version: block::Version::ONE,
+                prev_blockhash: BlockHash::all_zeros(),
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: 0,
+                bits: CompactTarget::from_consensus(0),
+                nonce: 0,
+            },
+            txdata: std::iter::once(coinbase).chain(reveals).map(|tx| tx.raw).collect(),
+        };
+        source_block(&block, Chain::Mainnet.first_inscription_height() + 50_000)
+    }
+
+    /// Block to benchmark: the mainnet block whose raw hex, as output by `bitcoin-cli getblock
+    /// <hash> 0`, is in the file at `ORDINALS_BENCH_BLOCK`, or else a synthetic heavy block
+    fn bench_block() -> (String, btc::Block) {
+        match std::env::var("ORDINALS_BENCH_BLOCK") {
+            Ok(path) => {
+                let raw = std::fs::read_to_string(&path).unwrap();
+                let block: bitcoin::Block = deserialize(&hex::decode(raw.trim()).unwrap()).unwrap();
+                let height = block.bip34_block_height().unwrap();
+                (format!("block {} ({})", height, path), source_block(&block, height))
+            }
+            Err(_) => ("synthetic block of 3000 inscriptions".into(), heavy_inscription_block(3_000)),
+        }
+    }
+
+    /// Sat ranges of the block, whose spent UTXOs are all assumed to hold enough sats
+    fn replay(block: &DecodedBlock) -> BlockSatRanges {
+        let spent_ranges = SatRanges {
+            ranges: vec![SatRange { start: 0, size: 2_100_000_000_000_000, ..Default::default() }],
+        };
+        assign_sat_ranges(block, |_| Some(spent_ranges.clone()))
+    }
+
+    /// Time taken by the modules of `substreams.yaml` to get the transactions and sat ranges of
+    /// a heavy block, when each module decodes the source block and replays its sat ranges, as
+    /// before, and when they read them from `map_decoded_block` and `map_block_sat_ranges`.
+    /// Blocks and outputs go through their protobuf encoding, like between modules.
+    /// Run with `ORDINALS_BENCH_BLOCK=<file> cargo test --release bench_ -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_heavy_block() {
+        // Modules reading the transactions of the block, and those of them also reading its sat ranges
+        const BLOCK_MODULES: usize = 21;
+        const SAT_RANGES_MODULES: usize = 9;
+        const ROUNDS: u32 = 5;
+
+        let (name, block) = bench_block();
+        let source = block.encode_to_vec();
+
+        // Transactions read by the modules, so that none of the work is optimized away
+        let mut read = 0;
+
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            for module in 0..BLOCK_MODULES {
+                let block = DecodedBlock::decode(&btc::Block::decode(source.as_slice()).unwrap(), Chain::Mainnet);
+                read += block.txs.len();
+                if module < SAT_RANGES_MODULES {
+                    read += replay(&block).txs.len();
+                }
+            }
+        }
+        let per_module = start.elapsed() / ROUNDS;
+
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            let block = DecodedBlock::decode(&btc::Block::decode(source.as_slice()).unwrap(), Chain::Mainnet);
+            let raw_block = block.encode().encode_to_vec();
+            let block_sat_ranges = ord_proto::BlockSatRanges::from(replay(&block)).encode_to_vec();
+
+            for module in 0..BLOCK_MODULES {
+                read -= DecodedBlock::from_raw(&ord_proto::RawBlock::decode(raw_block.as_slice()).unwrap()).txs.len();
+                if module < SAT_RANGES_MODULES {
+                    let block_sat_ranges = ord_proto::BlockSatRanges::decode(block_sat_ranges.as_slice()).unwrap();
+                    read -= BlockSatRanges::from(block_sat_ranges).txs.len();
+                }
+            }
+        }
+        let once = start.elapsed() / ROUNDS;
+
+        assert_eq!(read, 0);
+        println!("{}, {} txs", name, block.tx.len());
+        println!("decoded per module: {:?} per block", per_module);
+        println!("decoded once:       {:?} per block", once);
+        println!("speedup:            {:.2}x", per_module.as_secs_f64() / once.as_secs_f64());
+    }
+}
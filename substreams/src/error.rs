@@ -0,0 +1,86 @@
+use std::fmt;
+
+use crate::pb::ordinals::v1::{Diagnostic, DiagnosticKind};
+
+/// Malformed record found while indexing a block. Such records do not stop the
+/// indexing: they are skipped or worked around, and reported as diagnostics.
+#[derive(Debug)]
+pub enum Error {
+    InvalidHex { txid: String, source: hex::FromHexError },
+    InvalidTransaction { txid: String, source: bitcoin::consensus::encode::Error },
+    EmptyBlock,
+    /// UTXO spent by `txid` whose sat ranges are unknown
+    MissingSatRanges { txid: String, utxo: String },
+    /// UTXO spent by `txid` whose value is unknown
+    MissingValue { txid: String, utxo: String },
+}
+
+impl Error {
+    pub fn kind(&self) -> DiagnosticKind {
+        match self {
+            Error::InvalidHex { .. } => DiagnosticKind::InvalidHex,
+            Error::InvalidTransaction { .. } => DiagnosticKind::InvalidTransaction,
+            Error::EmptyBlock => DiagnosticKind::EmptyBlock,
+            Error::MissingSatRanges { .. } => DiagnosticKind::MissingSatRanges,
+            Error::MissingValue { .. } => DiagnosticKind::MissingValue,
+        }
+    }
+
+    /// Transaction holding the malformed record, if any
+    pub fn txid(&self) -> Option<&str> {
+        match self {
+            Error::InvalidHex { txid, .. }
+            | Error::InvalidTransaction { txid, .. }
+            | Error::MissingSatRanges { txid, .. }
+            | Error::MissingValue { txid, .. } => Some(txid),
+            Error::EmptyBlock => None,
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic {
+            kind: self.kind() as i32,
+            txid: self.txid().unwrap_or_default().to_string(),
+            message: self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidHex { txid, source } => write!(f, "invalid hex for transaction {}: {}", txid, source),
+            Error::InvalidTransaction { txid, source } => write!(f, "invalid raw transaction {}: {}", txid, source),
+            Error::EmptyBlock => write!(f, "block without any transaction"),
+            Error::MissingSatRanges { txid, utxo } => {
+                write!(f, "missing sat ranges for UTXO {} spent by {}", utxo, txid)
+            }
+            Error::MissingValue { txid, utxo } => write!(f, "missing value for UTXO {} spent by {}", utxo, txid),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InvalidHex { source, .. } => Some(source),
+            Error::InvalidTransaction { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Malformed records found while indexing a block, in the order they were found
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Diagnostics(pub Vec<Diagnostic>);
+
+impl Diagnostics {
+    pub fn report(&mut self, error: Error) {
+        substreams::log::info!("{}", error);
+        self.0.push(error.diagnostic());
+    }
+
+    pub fn append(&mut self, other: Diagnostics) {
+        self.0.extend(other.0);
+    }
+}
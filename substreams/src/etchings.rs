@@ -0,0 +1,183 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::decoded::{DecodedBlock, DecodedTransaction};
+use crate::ord::envelope::tapscripts;
+use crate::runes::rune::Rune;
+use crate::runes::rune_id::RuneId;
+use crate::runes::runestone::{Artifact, Runestone};
+
+/// Confirmations the output spent by the input committing to a rune name must
+/// have, counting the block of the etching
+pub const COMMIT_CONFIRMATIONS: u64 = 6;
+
+/// Runes etched by the transactions of `block`, by rune ID, as `ord` indexes them.
+///
+/// Etchings without a name get a reserved name. Named etchings are ignored if the
+/// name is shorter than the minimum name length at the block height, reserved,
+/// already etched earlier in the block, or if no input of the transaction reveals a
+/// tapscript committing to the name while spending a taproot output with enough
+/// confirmations. Cenotaphs etch their rune too, without any supply.
+///
+/// Names etched in previous blocks are not checked: `store_rune_ids` only keeps the
+/// first etching of a name. `commit_height` returns the height of the taproot UTXOs
+/// spent in the block.
+pub fn validate_etchings<F>(block: &DecodedBlock, mut commit_height: F) -> BTreeMap<RuneId, Rune>
+where
+    F: FnMut(&str) -> Option<u64>,
+{
+    let mut etched = BTreeMap::new();
+
+    if block.height < block.chain.first_rune_height() {
+        return etched;
+    }
+
+    let minimum = Rune::minimum_at_height(block.chain, block.height);
+    let mut names = HashSet::new();
+
+    for tx in &block.txs {
+        let rune = match Runestone::decipher(&tx.raw) {
+            Some(Artifact::Runestone(Runestone { etching: Some(etching), .. })) => etching.rune,
+            Some(Artifact::Cenotaph(cenotaph)) if cenotaph.etching.is_some() => cenotaph.etching,
+            _ => continue,
+        };
+
+        let rune = match rune {
+            Some(rune) => {
+                if rune < minimum
+                    || rune.is_reserved()
+                    || names.contains(&rune)
+                    || !commits_to_rune(tx, rune, block.height, &mut commit_height)
+                {
+                    continue;
+                }
+                rune
+            }
+            None => Rune::reserved(block.height, tx.idx),
+        };
+
+        names.insert(rune);
+        etched.insert(RuneId { block: block.height, tx: tx.idx }, rune);
+    }
+
+    etched
+}
+
+/// Whether an input of `tx` pushes the commitment to `rune` in its tapscript while
+/// spending a taproot output confirmed at least `COMMIT_CONFIRMATIONS` times at `height`
+fn commits_to_rune<F>(tx: &DecodedTransaction, rune: Rune, height: u64, commit_height: &mut F) -> bool
+where
+    F: FnMut(&str) -> Option<u64>,
+{
+    let commitment = rune.commitment();
+    let inputs = tx.inputs().collect::<Vec<_>>();
+
+    for (input, tapscript) in tapscripts(&tx.raw) {
+        for instruction in tapscript.instructions() {
+            // The tapscript may be invalid past the commitment
+            let instruction = match instruction {
+                Ok(instruction) => instruction,
+                Err(_) => break,
+            };
+
+            if instruction.push_bytes().map(|push| push.as_bytes()) != Some(commitment.as_slice()) {
+                continue;
+            }
+
+            // Only the heights of the taproot UTXOs are known
+            match commit_height(&inputs[input]) {
+                Some(commit_height) if height.saturating_sub(commit_height) + 1 >= COMMIT_CONFIRMATIONS => return true,
+                _ => {}
+            }
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::blockdata::script::{self, PushBytesBuf};
+    use bitcoin::Witness;
+
+    use super::*;
+    use crate::decoded::test_utils::{block, tx, utxo};
+    use crate::ord::chain::Chain;
+    use crate::runes::test_utils::runestone_tx;
+
+    /// Height from which any name long enough can be etched on mainnet
+    const HEIGHT: u64 = 1_050_000;
+
+    /// Transaction etching with a runestone made of `integers`, whose input spends
+    /// output 0 of `commit` and reveals a tapscript pushing `commitment`
+    fn etching_tx(name: &str, commit: &str, commitment: &[u8], integers: &[u128]) -> DecodedTransaction {
+        let mut tx = runestone_tx(name, &[(commit, 0)], integers, 1);
+
+        let tapscript = script::Builder::new()
+            .push_slice(PushBytesBuf::try_from(commitment.to_vec()).unwrap())
+            .push_opcode(bitcoin::opcodes::all::OP_DROP)
+            .into_script();
+        tx.raw.input[0].witness = Witness::from_slice(&[tapscript.into_bytes(), vec![0xc0; 33]]);
+        tx
+    }
+
+    fn named(name: &str, commit: &str, rune: Rune) -> DecodedTransaction {
+        etching_tx(name, commit, &rune.commitment(), &[2, 1, 4, rune.0])
+    }
+
+    #[test]
+    fn test_validate_etchings() {
+        let rune = |name: &str| name.parse::<Rune>().unwrap();
+        let block = block(HEIGHT, vec![
+            tx("cb", &[], &[]),
+            named("a", "commit a", rune("A")),
+            // Not enough confirmations
+            named("b", "recent", rune("B")),
+            // Commits to another name
+            etching_tx("c", "commit c", &rune("Z").commitment(), &[2, 1, 4, rune("C").0]),
+            // Spends an output which is not a taproot one
+            named("d", "not taproot", rune("D")),
+            // Etched earlier in the block
+            named("e", "commit e", rune("A")),
+            named("f", "commit f", Rune(Rune::RESERVED)),
+            // Etchings without a name do not need a commitment
+            etching_tx("g", "not taproot", &[], &[2, 1]),
+            // Cenotaphs etch their rune too
+            etching_tx("h", "commit h", &rune("H").commitment(), &[2, 1, 4, rune("H").0, 24, 1]),
+        ]);
+
+        let commit_height = |utxo: &str| {
+            if utxo == self::utxo("recent", 0) {
+                Some(HEIGHT - COMMIT_CONFIRMATIONS + 2)
+            } else if utxo == self::utxo("not taproot", 0) {
+                None
+            } else {
+                Some(HEIGHT - COMMIT_CONFIRMATIONS + 1)
+            }
+        };
+        let etched = validate_etchings(&block, commit_height);
+
+        let id = |tx: u32| RuneId { block: HEIGHT, tx };
+        assert_eq!(etched, BTreeMap::from([
+            (id(1), rune("A")),
+            (id(7), Rune::reserved(HEIGHT, 7)),
+            (id(8), rune("H")),
+        ]));
+    }
+
+    #[test]
+    fn test_minimum_name_length() {
+        let height = Chain::Mainnet.first_rune_height();
+        let short = "ZZYZXBRKWXUZ".parse::<Rune>().unwrap();
+        let block = |height| block(height, vec![
+            tx("cb", &[], &[]),
+            named("a", "commit", short),
+        ]);
+
+        assert!(validate_etchings(&block(height - 1), |_| Some(0)).is_empty());
+        assert!(validate_etchings(&block(height), |_| Some(0)).is_empty());
+        assert_eq!(
+            validate_etchings(&block(height + 1), |_| Some(0)),
+            BTreeMap::from([(RuneId { block: height + 1, tx: 1 }, short)]),
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use crate::decoded::DecodedBlock;
+use crate::error::{Diagnostics, Error};
+
+/// Fees paid by the transactions of a block
+#[derive(Debug, Default, PartialEq)]
+pub struct BlockFees {
+    /// Fee paid by each non-coinbase transaction (in sats), in block order
+    pub fees: Vec<u64>,
+    /// UTXOs spent without known value
+    pub diagnostics: Diagnostics,
+}
+
+/// Fee paid by each non-coinbase transaction of `block` (in sats), in block order.
+/// Since inputs do not carry their value, `lookup` returns the value of UTXOs
+/// created in previous blocks. UTXOs created and spent within the block are
+/// resolved locally. UTXOs which cannot be resolved are reported and assumed to
+/// hold no value.
+pub fn transaction_fees<F>(block: &DecodedBlock, mut lookup: F) -> BlockFees
+where
+    F: FnMut(&str) -> Option<u64>,
+{
+    let mut created: HashMap<String, u64> = HashMap::new();
+    let mut diagnostics = Diagnostics::default();
+
+    let fees = block.txs.iter().skip(1)
+        .map(|tx| {
+            let input_value: u64 = tx.inputs()
+                .map(|utxo| {
+                    created.remove(&utxo)
+                        .or_else(|| lookup(&utxo))
+                        .unwrap_or_else(|| {
+                            diagnostics.report(Error::MissingValue { txid: tx.txid.clone(), utxo });
+                            0
+                        })
+                })
+                .sum();
+
+            for (vout, value) in tx.output_values().enumerate() {
+                created.insert(tx.utxo(vout), value);
+            }
+
+            input_value.saturating_sub(tx.amount())
+        })
+        .collect();
+
+    BlockFees { fees, diagnostics }
+}
+
+/// Fee rate in sat/vB
+pub fn fee_rate(fee: u64, vsize: i32) -> f64 {
+    if vsize <= 0 {
+        return 0.;
+    }
+    fee as f64 / vsize as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoded::test_utils::{block, tx, utxo};
+    use crate::pb::ordinals::v1::DiagnosticKind;
+
+    #[test]
+    fn test_transaction_fees() {
+        let block = block(1, vec![
+            tx("cb", &[], &[]),
+            tx("a", &[("prev", 0)], &[1_000, 2_000]),
+            tx("b", &[("a", 1)], &[1_500]),
+        ]);
+
+        let fees = transaction_fees(&block, |u| (u == utxo("prev", 0)).then_some(3_500));
+        assert_eq!(fees, BlockFees { fees: vec![500, 500], ..Default::default() });
+
+        // Unknown UTXOs are reported and assumed to hold no value
+        let fees = transaction_fees(&block, |_| None);
+        assert_eq!(fees.fees, vec![0, 500]);
+        assert_eq!(fees.diagnostics.0.len(), 1);
+        assert_eq!(fees.diagnostics.0[0].kind, DiagnosticKind::MissingValue as i32);
+    }
+
+    #[test]
+    fn test_fee_rate() {
+        assert_eq!(fee_rate(1_410, 141), 10.);
+        assert_eq!(fee_rate(1_000, 0), 0.);
+    }
+}
@@ -0,0 +1,208 @@
+use std::str::FromStr;
+
+use substreams::scalar::{BigDecimal, BigInt};
+use substreams_entity_change::tables::Tables;
+
+use crate::pb::ordinals::v1::{self as ord_proto, InscriptionState, InscriptionTransfers, OrdinalBlock, Rarity, SatRanges};
+use crate::sat_ranges::format_sat_ranges;
+
+/// Adds the changes made by `block` to the entities of `subgraph/schema.graphql` to `tables`
+pub fn block_entity_changes(tables: &mut Tables, block: &ord_proto::Block, transfers: &InscriptionTransfers) {
+    let block_id = block.number.to_string();
+    tables.create_row("Block", &block_id)
+        .set("height", BigInt::from(block.number))
+        .set("timestamp", BigInt::from(block.timestamp))
+        .set("reward", BigInt::from(block.miner_reward))
+        .set("subsidy", BigInt::from(block.subsidy))
+        .set("fees", BigInt::from(block.fees));
+
+    let coinbase_outputs = block.txs.first()
+        .map(|tx| tx.coinbase_ordinals.as_slice())
+        .unwrap_or_default();
+
+    for tx in &block.txs {
+        tables.create_row("Transaction", &tx.txid)
+            .set("idx", BigInt::from(tx.idx))
+            .set("amount", BigInt::from(tx.amount))
+            .set("fee", BigInt::from(tx.fee))
+            .set("feeRate", BigDecimal::from_str(&tx.fee_rate.to_string()).unwrap())
+            .set("block", &block_id);
+
+        for utxo in &tx.input_utxos {
+            tables.update_row("Utxo", utxo)
+                .set("unspent", false)
+                .set("spentIn", &tx.txid);
+        }
+
+        // Only one of them is set, depending on whether the transaction is the coinbase
+        for output in tx.coinbase_ordinals.iter().chain(&tx.relative_ordinals) {
+            let row = tables.create_row("Utxo", &output.utxo);
+            row.set("amount", BigInt::from(output.size))
+                .set("satRanges", format_sat_ranges(&output.absolute_ordinals))
+                .set("unspent", true)
+                .set("transaction", &tx.txid);
+            if let Some(address) = &output.address {
+                row.set("address", address);
+            }
+        }
+
+        for inscription in &tx.inscriptions {
+            let row = tables.create_row("Inscription", &inscription.id);
+            row.set("number", BigInt::from(inscription.number))
+                .set("cursed", inscription.cursed)
+                .set("rarity", Rarity::from_i32(inscription.rarity).unwrap_or_default().as_str_name())
+                .set("reinscriptionOf", &inscription.reinscription_of)
+                .set("content", &inscription.content)
+                .set("contentLength", BigInt::from(inscription.content_length.parse::<u64>().unwrap_or_default()))
+                .set("offset", BigInt::from(inscription.pointer.unwrap_or_default()))
+                .set("genesisTransaction", &tx.txid)
+                .set("state", InscriptionState::Transferred.as_str_name());
+
+            let optional_fields = [
+                ("content_type", &inscription.content_type),
+                ("parent", &inscription.parent),
+                ("metadata", &inscription.metadata),
+                ("metaprotocol", &inscription.metaprotocol),
+                ("contentEncoding", &inscription.content_encoding),
+            ];
+            for (name, value) in optional_fields {
+                if let Some(value) = value {
+                    row.set(name, value);
+                }
+            }
+
+            // Unbound inscriptions are not made on any sat
+            let sat = match inscription.sat {
+                Some(sat) => sat,
+                None => continue,
+            };
+            row.set("ordinal", BigInt::from(sat));
+
+            // Inscribed sats paid as fee are held by a coinbase output, if any
+            let genesis = output_holding(&tx.relative_ordinals, sat)
+                .or_else(|| output_holding(coinbase_outputs, sat));
+            if let Some((output, offset)) = genesis {
+                row.set("genesisUtxo", &output.utxo)
+                    .set("genesisOffset", BigInt::from(offset))
+                    .set("location", &output.utxo)
+                    .set("locationOffset", BigInt::from(offset));
+                if let Some(address) = &output.address {
+                    row.set("genesisAddress", address);
+                }
+            }
+        }
+    }
+
+    for transfer in &transfers.transfers {
+        let state = InscriptionState::from_i32(transfer.state).unwrap_or_default();
+        let row = tables.update_row("Inscription", &transfer.inscription_id);
+        row.set("state", state.as_str_name());
+
+        // Lost inscriptions are not held by any UTXO, their last location is kept
+        if state != InscriptionState::Lost {
+            row.set("location", &transfer.to_outpoint)
+                .set("locationOffset", BigInt::from(transfer.offset));
+        }
+    }
+}
+
+/// Output holding `sat`, along with the offset of the sat within it
+fn output_holding(outputs: &[OrdinalBlock], sat: u64) -> Option<(&OrdinalBlock, u64)> {
+    outputs.iter().find_map(|output| {
+        SatRanges { ranges: output.absolute_ordinals.clone() }
+            .offset_of(sat)
+            .map(|offset| (output, offset))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use substreams_entity_change::pb::entity::{entity_change::Operation, value::Typed, EntityChange};
+
+    use super::*;
+    use crate::pb::ordinals::v1::{InscriptionTransfer, SatRange};
+
+    fn field(change: &EntityChange, name: &str) -> Option<Typed> {
+        change.fields.iter()
+            .find(|field| field.name == name)
+            .and_then(|field| field.new_value.clone())
+            .and_then(|value| value.typed)
+    }
+
+    fn output(utxo: &str, start: u64, size: u64) -> OrdinalBlock {
+        OrdinalBlock {
+            utxo: utxo.into(),
+            size,
+            absolute_ordinals: vec![SatRange { start, size, ..Default::default() }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_block_entity_changes() {
+        let block = ord_proto::Block {
+            number: 800_000,
+            txs: vec![
+                ord_proto::Transaction {
+                    txid: "cb".into(),
+                    coinbase_ordinals: vec![output("cb:0", 1_000, 10)],
+                    ..Default::default()
+                },
+                ord_proto::Transaction {
+                    txid: "a".into(),
+                    idx: 1,
+                    input_utxos: vec!["prev:0".into()],
+                    relative_ordinals: vec![output("a:0", 100, 5), output("a:1", 105, 5)],
+                    inscriptions: vec![
+                        ord_proto::Inscription { id: "ai0".into(), sat: Some(107), ..Default::default() },
+                        // Unbound
+                        ord_proto::Inscription { id: "ai1".into(), ..Default::default() },
+                    ],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let transfers = InscriptionTransfers {
+            transfers: vec![
+                InscriptionTransfer {
+                    inscription_id: "old".into(),
+                    to_outpoint: "a:0".into(),
+                    offset: 3,
+                    ..Default::default()
+                },
+                InscriptionTransfer {
+                    inscription_id: "lost".into(),
+                    state: InscriptionState::Lost as i32,
+                    ..Default::default()
+                },
+            ],
+        };
+
+        let mut tables = Tables::new();
+        block_entity_changes(&mut tables, &block, &transfers);
+        let changes = tables.to_entity_changes().entity_changes;
+
+        let change = |entity: &str, id: &str| {
+            changes.iter()
+                .find(|change| change.entity == entity && change.id == id)
+                .unwrap()
+        };
+
+        assert_eq!(field(change("Utxo", "a:1"), "satRanges"), Some(Typed::String("105:5".into())));
+        assert_eq!(change("Utxo", "prev:0").operation, Operation::Update as i32);
+        assert_eq!(field(change("Utxo", "prev:0"), "spentIn"), Some(Typed::String("a".into())));
+
+        assert_eq!(field(change("Inscription", "ai0"), "location"), Some(Typed::String("a:1".into())));
+        assert_eq!(field(change("Inscription", "ai0"), "locationOffset"), Some(Typed::Bigint("2".into())));
+        assert_eq!(field(change("Inscription", "ai1"), "location"), None);
+
+        assert_eq!(change("Inscription", "old").operation, Operation::Update as i32);
+        assert_eq!(field(change("Inscription", "old"), "location"), Some(Typed::String("a:0".into())));
+        assert_eq!(field(change("Inscription", "lost"), "location"), None);
+        assert_eq!(
+            field(change("Inscription", "lost"), "state"),
+            Some(Typed::String("INSCRIPTION_STATE_LOST".into())),
+        );
+    }
+}
@@ -0,0 +1,467 @@
+use std::collections::BTreeMap;
+
+use crate::decoded::{DecodedBlock, DecodedTransaction};
+use crate::ord::{curse::Curse, envelope::ParsedEnvelope};
+use crate::pb::ordinals::v1::{self as ord_proto, Inscription, Rarity, SatInscriptions, SatRanges};
+use crate::sat_ranges::BlockSatRanges;
+use crate::sats_utils::{subsidy, Sat};
+
+/// Inscriptions revealed in a block, along with the updated inscriptions of
+/// every sat they were made on
+#[derive(Debug, Default, PartialEq)]
+pub struct BlockInscriptions {
+    pub inscriptions: Vec<Inscription>,
+    pub sat_inscriptions: BTreeMap<u64, SatInscriptions>,
+}
+
+/// Indexes the inscriptions revealed in `block`: each inscription is made on the
+/// first sat of the input holding its envelope, or on the sat its pointer points
+/// to, and is a reinscription if the first sat of its input is already inscribed.
+///
+/// Inscriptions are returned in the order `ord` numbers them: transaction by
+/// transaction, by offset of their sat within the outputs. Inscriptions whose sat
+/// is paid as fee are numbered along with those of the coinbase, which `ord`
+/// processes last.
+///
+/// `lookup` returns the inscriptions made on a sat in previous blocks.
+pub fn index_inscriptions<F>(
+    block: &DecodedBlock,
+    block_sat_ranges: &BlockSatRanges,
+    mut lookup: F,
+) -> BlockInscriptions
+where
+    F: FnMut(u64) -> Option<SatInscriptions>,
+{
+    let height = block.height;
+    let jubilant = block.chain.is_jubilant(height);
+    let mut result = BlockInscriptions::default();
+    // Numbering order of each inscription: index of the transaction processing it,
+    // the coinbase being last, and offset of its sat within the transaction
+    let mut numbering = vec![];
+    // Sats paid to the coinbase by the transactions processed so far
+    let mut reward = subsidy(height);
+
+    // Inscriptions made on a sat, either by earlier transactions of the block or in previous blocks
+    let mut inscriptions_on = |sat: u64, block_sat_inscriptions: &BTreeMap<u64, SatInscriptions>| {
+        block_sat_inscriptions.get(&sat).cloned().or_else(|| lookup(sat))
+    };
+
+    for (position, (tx, tx_sat_ranges)) in block.txs.iter().zip(&block_sat_ranges.txs).enumerate() {
+        let fee_offset = reward;
+        if position > 0 {
+            reward += tx_sat_ranges.fee.value();
+        }
+
+        let envelopes = parse_envelopes(block, tx);
+        if envelopes.is_empty() {
+            continue;
+        }
+
+        let mut input_ranges = SatRanges::default();
+        let mut input_offsets = vec![];
+        for (_, ranges) in &tx_sat_ranges.inputs {
+            input_offsets.push(input_ranges.value());
+            input_ranges.append(ranges.clone());
+        }
+        let total_output_value = tx.amount();
+
+        // Inscriptions made on sats by the transaction, only visible to the following
+        // transactions: like `ord`, reinscriptions are checked against the sats as they
+        // were before the transaction
+        let mut tx_sat_inscriptions: BTreeMap<u64, SatInscriptions> = BTreeMap::new();
+
+        for (idx, envelope) in envelopes.into_iter().enumerate() {
+            let mut inscription = inscription(&tx.txid, idx, &envelope);
+
+            let input = envelope.input as usize;
+            let input_value = tx_sat_ranges.inputs.get(input)
+                .map(|(_, ranges)| ranges.value())
+                .unwrap_or_default();
+
+            // Inscriptions on inputs without any sat, or with unrecognized even
+            // fields, are unbound: they are not made on any sat
+            let input_sat = if input_value == 0 || envelope.payload.unrecognized_even_field {
+                None
+            } else {
+                input_ranges.nth(input_offsets[input])
+            };
+
+            // The pointer moves the inscription to another sat of the transaction,
+            // unless it points beyond the sats held by the outputs
+            let pointer = envelope.payload.pointer().filter(|&pointer| pointer < total_output_value);
+            let sat = input_sat.map(|input_sat| {
+                pointer.and_then(|pointer| input_ranges.nth(pointer)).unwrap_or(input_sat)
+            });
+
+            let offset = pointer.unwrap_or_else(|| input_offsets.get(input).copied().unwrap_or_default());
+            numbering.push(if position == 0 {
+                (block.txs.len(), offset)
+            } else if offset < total_output_value {
+                (position, offset)
+            } else {
+                (block.txs.len(), fee_offset + offset - total_output_value)
+            });
+
+            // Reinscriptions are checked on the first sat of the envelope's input
+            // before the transaction
+            let previous = input_sat.and_then(|sat| inscriptions_on(sat, &result.sat_inscriptions));
+
+            let reinscription_curse = match &previous {
+                // The first reinscription of a sat whose first inscription was
+                // cursed or vindicated is not cursed
+                Some(previous) if previous.inscriptions.len() > 1 => Some(Curse::Reinscription),
+                Some(previous) if previous.inscriptions.len() == 1 && !previous.first_cursed_or_vindicated => {
+                    Some(Curse::Reinscription)
+                }
+                _ => None,
+            };
+            let curse = Curse::from_envelope(&envelope).or(reinscription_curse);
+
+            // Curses are not applied anymore after the jubilee
+            inscription.cursed = curse.is_some() && !jubilant;
+            inscription.vindicated = curse.is_some() && jubilant;
+            inscription.curse = curse.map_or(ord_proto::Curse::None, ord_proto::Curse::from) as i32;
+
+            inscription.sat = sat;
+            inscription.rarity = sat.map_or(Rarity::Common, |sat| Sat(sat).rarity()) as i32;
+            inscription.sat_name = sat.map(|sat| Sat(sat).name());
+            inscription.sat_degree = sat.map(|sat| Sat(sat).degree());
+            inscription.sat_decimal = sat.map(|sat| Sat(sat).decimal());
+
+            if let Some(sat) = sat {
+                let mut sat_inscriptions = match tx_sat_inscriptions.remove(&sat) {
+                    Some(sat_inscriptions) => Some(sat_inscriptions),
+                    None if Some(sat) == input_sat => previous,
+                    None => inscriptions_on(sat, &result.sat_inscriptions),
+                }
+                .unwrap_or_default();
+                if sat_inscriptions.inscriptions.is_empty() {
+                    sat_inscriptions.first_cursed_or_vindicated = curse.is_some();
+                }
+                inscription.reinscription_of = sat_inscriptions.inscriptions.clone();
+                sat_inscriptions.inscriptions.push(inscription.id.clone());
+                tx_sat_inscriptions.insert(sat, sat_inscriptions);
+            }
+
+            result.inscriptions.push(inscription);
+        }
+
+        result.sat_inscriptions.append(&mut tx_sat_inscriptions);
+    }
+
+    // Stable sort, inscriptions of the same sat keep the order they were made in
+    let mut numbered = numbering.into_iter().zip(result.inscriptions).collect::<Vec<_>>();
+    numbered.sort_by_key(|(order, _)| *order);
+    result.inscriptions = numbered.into_iter().map(|(_, inscription)| inscription).collect();
+
+    result
+}
+
+fn parse_envelopes(block: &DecodedBlock, tx: &DecodedTransaction) -> Vec<ParsedEnvelope> {
+    if block.height < block.chain.first_inscription_height() {
+        return vec![];
+    }
+
+    ParsedEnvelope::from_transaction(&tx.raw)
+}
+
+fn inscription(txid: &str, idx: usize, envelope: &ParsedEnvelope) -> Inscription {
+    Inscription {
+        id: format!("{}i{}", txid, idx),
+        content_type: envelope.payload.content_type().map(|s| s.to_string()),
+        content_length: envelope.payload.content_length().map(|s| s.to_string()).unwrap_or("0".into()),
+        pointer: envelope.payload.pointer().map(|ptr| ptr as i64),
+        parent: envelope.payload.parent().map(|parent| parent.to_string()),
+        metadata: envelope.payload.metadata.clone().map(|metadata| match String::from_utf8(metadata.clone()) {
+            Ok(metadata) => metadata,
+            Err(_) => hex::encode(metadata)
+        }),
+        metaprotocol: envelope.payload.metaprotocol().map(|s| s.to_string()),
+        content_encoding: envelope.payload.content_encoding().map(|s| match String::from_utf8(s.as_ref().to_vec()) {
+            Ok(content_type) => content_type,
+            Err(_) => hex::encode(s.as_ref())
+        }),
+        content: match String::from_utf8(envelope.payload.body().unwrap_or_default().to_vec()) {
+            Ok(content) => content,
+            Err(_) => hex::encode(envelope.payload.body().unwrap_or_default())
+        },
+        // Assigned once all the inscriptions of the block are known
+        number: 0,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{blockdata::script, Witness};
+
+    use super::*;
+    use crate::decoded::test_utils::{block, tx};
+    use crate::ord::chain::Chain;
+    use crate::ord::inscription::Inscription as Payload;
+    use crate::sat_ranges::test_utils::{ranges, utxos};
+    use crate::sat_ranges::TransactionSatRanges;
+
+    /// Transaction revealing `payloads` in the witness of its input `envelope_input`
+    fn reveal_tx(txid: &str, inputs: &[(&str, u32)], envelope_input: usize, payloads: &[Payload]) -> DecodedTransaction {
+        let mut reveal = tx(txid, inputs, &[]);
+        // Short txids keep the inscription ids readable
+        reveal.txid = txid.into();
+
+        let script = Payload::append_batch_reveal_script(payloads, script::Builder::new());
+        reveal.raw.input[envelope_input].witness = Witness::from_slice(&[script.into_bytes(), vec![0xc0]]);
+        reveal
+    }
+
+    /// Sat ranges of a transaction spending `inputs`
+    fn sat_ranges(inputs: &[(&str, &[(u64, u64)])]) -> TransactionSatRanges {
+        TransactionSatRanges { inputs: utxos(inputs), ..Default::default() }
+    }
+
+    fn text(body: &str) -> Payload {
+        Payload::new(Some(b"text/plain".to_vec()), Some(body.as_bytes().to_vec()))
+    }
+
+    #[test]
+    fn test_reinscriptions() {
+        let block = block(Chain::Mainnet.first_inscription_height(), vec![
+            tx("cb", &[], &[]),
+            reveal_tx("a", &[("prev", 0)], 0, &[text("first")]),
+            reveal_tx("b", &[("a", 0)], 0, &[text("second"), text("third")]),
+        ]);
+        let block_sat_ranges = BlockSatRanges {
+            txs: vec![
+                TransactionSatRanges::default(),
+                sat_ranges(&[("prev:0", &[(100, 10)])]),
+                sat_ranges(&[("a:0", &[(100, 10)])]),
+            ],
+            ..Default::default()
+        };
+
+        let result = index_inscriptions(&block, &block_sat_ranges, |_| None);
+
+        let inscriptions = &result.inscriptions;
+        assert_eq!(inscriptions.len(), 3);
+        assert!(!inscriptions[0].cursed);
+        assert!(inscriptions[0].reinscription_of.is_empty());
+        assert!(inscriptions[1].cursed);
+        assert_eq!(inscriptions[1].curse, ord_proto::Curse::Reinscription as i32);
+        assert_eq!(inscriptions[1].reinscription_of, vec!["ai0".to_string()]);
+        assert_eq!(inscriptions[2].reinscription_of, vec!["ai0".to_string(), "bi0".to_string()]);
+
+        assert_eq!(result.sat_inscriptions.len(), 1);
+        assert_eq!(result.sat_inscriptions[&100], SatInscriptions {
+            inscriptions: vec!["ai0".into(), "bi0".into(), "bi1".into()],
+            first_cursed_or_vindicated: false,
+        });
+    }
+
+    #[test]
+    fn test_reinscription_of_cursed_inscription() {
+        let block = block(Chain::Mainnet.first_inscription_height(), vec![
+            tx("cb", &[], &[]),
+            reveal_tx("b", &[("a", 0)], 0, &[text("second")]),
+            reveal_tx("c", &[("b", 0)], 0, &[text("third")]),
+        ]);
+        let block_sat_ranges = BlockSatRanges {
+            txs: vec![
+                TransactionSatRanges::default(),
+                sat_ranges(&[("a:0", &[(100, 10)])]),
+                sat_ranges(&[("b:0", &[(100, 10)])]),
+            ],
+            ..Default::default()
+        };
+
+        // The sat was first inscribed by a cursed inscription in a previous block
+        let result = index_inscriptions(&block, &block_sat_ranges, |sat| match sat {
+            100 => Some(SatInscriptions {
+                inscriptions: vec!["ai0".into()],
+                first_cursed_or_vindicated: true,
+            }),
+            _ => None,
+        });
+
+        assert!(!result.inscriptions[0].cursed);
+        assert_eq!(result.inscriptions[0].reinscription_of, vec!["ai0".to_string()]);
+        assert!(result.inscriptions[1].cursed);
+        assert_eq!(result.inscriptions[1].curse, ord_proto::Curse::Reinscription as i32);
+    }
+
+    #[test]
+    fn test_reinscriptions_in_same_input() {
+        let block = block(Chain::Mainnet.first_inscription_height(), vec![
+            tx("cb", &[], &[]),
+            reveal_tx("b", &[("a", 0)], 0, &[text("second"), text("third")]),
+        ]);
+        let block_sat_ranges = BlockSatRanges {
+            txs: vec![TransactionSatRanges::default(), sat_ranges(&[("a:0", &[(100, 10)])])],
+            ..Default::default()
+        };
+
+        // The sat was first inscribed by a cursed inscription in a previous block
+        let result = index_inscriptions(&block, &block_sat_ranges, |sat| match sat {
+            100 => Some(SatInscriptions {
+                inscriptions: vec!["ai0".into()],
+                first_cursed_or_vindicated: true,
+            }),
+            _ => None,
+        });
+
+        // The second envelope is checked against the sat as it was before the
+        // transaction, it is only cursed for not being the first of its input
+        assert!(!result.inscriptions[0].cursed);
+        assert!(result.inscriptions[1].cursed);
+        assert_eq!(result.inscriptions[1].curse, ord_proto::Curse::NotAtOffsetZero as i32);
+        assert_eq!(result.inscriptions[1].reinscription_of, vec!["ai0".to_string(), "bi0".to_string()]);
+        assert_eq!(result.sat_inscriptions[&100], SatInscriptions {
+            inscriptions: vec!["ai0".into(), "bi0".into(), "bi1".into()],
+            first_cursed_or_vindicated: true,
+        });
+    }
+
+    #[test]
+    fn test_sat_inscribed_twice_in_block() {
+        let pointer = |body: &str, pointer: u64| Payload {
+            pointer: Some(Payload::pointer_value(pointer)),
+            ..text(body)
+        };
+
+        // The first reveal checks the sat for reinscriptions but moves its inscription
+        let mut moved = reveal_tx("b", &[("a", 0)], 0, &[pointer("moved", 5)]);
+        moved.raw.output = tx("outputs", &[], &[10]).raw.output;
+        let block = block(Chain::Mainnet.first_inscription_height(), vec![
+            tx("cb", &[], &[]),
+            moved,
+            reveal_tx("c", &[("b", 0)], 0, &[text("reinscription")]),
+        ]);
+        let block_sat_ranges = BlockSatRanges {
+            txs: vec![
+                TransactionSatRanges::default(),
+                sat_ranges(&[("a:0", &[(100, 10)])]),
+                sat_ranges(&[("b:0", &[(100, 10)])]),
+            ],
+            ..Default::default()
+        };
+
+        let previous = BTreeMap::from([(100, SatInscriptions { inscriptions: vec!["ai0".into()], ..Default::default() })]);
+        let result = index_inscriptions(&block, &block_sat_ranges, |sat| previous.get(&sat).cloned());
+
+        assert_eq!(result.inscriptions[0].sat, Some(105));
+        assert!(result.inscriptions[0].reinscription_of.is_empty());
+        assert_eq!(result.inscriptions[1].sat, Some(100));
+        assert_eq!(result.inscriptions[1].reinscription_of, vec!["ai0".to_string()]);
+        assert_eq!(result.sat_inscriptions[&100].inscriptions, vec!["ai0", "ci0"]);
+    }
+
+    #[test]
+    fn test_inscribed_sats() {
+        let mut reveal = reveal_tx("a", &[("prev", 0), ("prev", 1)], 1, &[text("second input")]);
+        reveal.raw.output = tx("outputs", &[], &[15]).raw.output;
+        let block = block(Chain::Mainnet.first_inscription_height(), vec![
+            tx("cb", &[], &[]),
+            reveal,
+            reveal_tx("b", &[("prev", 2)], 0, &[text("unbound")]),
+        ]);
+        let block_sat_ranges = BlockSatRanges {
+            txs: vec![
+                TransactionSatRanges::default(),
+                sat_ranges(&[("prev:0", &[(100, 10)]), ("prev:1", &[(5_000_000_000, 5)])]),
+                sat_ranges(&[("prev:2", &[(0, 0)])]),
+            ],
+            ..Default::default()
+        };
+
+        let result = index_inscriptions(&block, &block_sat_ranges, |_| None);
+
+        // Made on the first sat of its input
+        assert_eq!(result.inscriptions[0].sat, Some(5_000_000_000));
+        assert_eq!(result.inscriptions[0].rarity, Rarity::Uncommon as i32);
+        assert_eq!(result.inscriptions[0].sat_degree.as_deref(), Some("0°1′1″0‴"));
+        assert_eq!(result.inscriptions[0].sat_decimal.as_deref(), Some("1.0"));
+        assert_eq!(result.inscriptions[0].curse, ord_proto::Curse::NotInFirstInput as i32);
+        // Revealed in an input without any sat
+        assert_eq!(result.inscriptions[1].sat, None);
+        assert_eq!(result.inscriptions[1].sat_name, None);
+        assert_eq!(result.sat_inscriptions.keys().collect::<Vec<_>>(), vec![&5_000_000_000]);
+    }
+
+    #[test]
+    fn test_numbering_order() {
+        let pointer = |body: &str, pointer: u64| Payload {
+            pointer: Some(Payload::pointer_value(pointer)),
+            ..text(body)
+        };
+        let outputs = tx("outputs", &[], &[10]).raw.output;
+
+        let mut pointed = reveal_tx("a", &[("prev", 0)], 0, &[pointer("pointed", 3), text("second")]);
+        pointed.raw.output = outputs.clone();
+        let mut last = reveal_tx("c", &[("prev", 2)], 0, &[text("last")]);
+        last.raw.output = outputs;
+        let block = block(Chain::Mainnet.first_inscription_height(), vec![
+            tx("cb", &[], &[]),
+            pointed,
+            // All the sats are paid as fee
+            reveal_tx("b", &[("prev", 1)], 0, &[text("fee")]),
+            last,
+        ]);
+        let block_sat_ranges = BlockSatRanges {
+            txs: vec![
+                TransactionSatRanges::default(),
+                sat_ranges(&[("prev:0", &[(100, 10)])]),
+                TransactionSatRanges {
+                    fee: ranges(&[(500, 10)]),
+                    ..sat_ranges(&[("prev:1", &[(500, 10)])])
+                },
+                sat_ranges(&[("prev:2", &[(700, 10)])]),
+            ],
+            ..Default::default()
+        };
+
+        let result = index_inscriptions(&block, &block_sat_ranges, |_| None);
+
+        // By offset within each transaction, those paid as fee with the coinbase
+        assert_eq!(
+            result.inscriptions.iter().map(|inscription| inscription.id.as_str()).collect::<Vec<_>>(),
+            vec!["ai1", "ai0", "ci0", "bi0"],
+        );
+        assert_eq!(result.inscriptions[3].sat, Some(500));
+    }
+
+    #[test]
+    fn test_batch_reveal_pointers() {
+        let pointer = |body: &str, pointer: u64| Payload {
+            pointer: Some(Payload::pointer_value(pointer)),
+            ..text(body)
+        };
+
+        let mut reveal = reveal_tx("a", &[("prev", 0), ("prev", 1)], 0, &[
+            text("no pointer"),
+            pointer("second output", 7),
+            pointer("second input", 12),
+            // Points to the sats paid as fee
+            pointer("fee", 15),
+            pointer("beyond inputs", 1_000),
+        ]);
+        reveal.raw.output = tx("outputs", &[], &[5, 10]).raw.output;
+
+        let block = block(Chain::Mainnet.first_inscription_height(), vec![tx("cb", &[], &[]), reveal]);
+        let block_sat_ranges = BlockSatRanges {
+            txs: vec![
+                TransactionSatRanges::default(),
+                sat_ranges(&[("prev:0", &[(100, 10)]), ("prev:1", &[(500, 10)])]),
+            ],
+            ..Default::default()
+        };
+
+        let result = index_inscriptions(&block, &block_sat_ranges, |_| None);
+
+        // Numbered by offset: the inscriptions whose pointer is ignored stay on the first sat
+        assert_eq!(
+            result.inscriptions.iter()
+                .map(|inscription| (inscription.id.as_str(), inscription.sat))
+                .collect::<Vec<_>>(),
+            vec![("ai0", Some(100)), ("ai3", Some(100)), ("ai4", Some(100)), ("ai1", Some(107)), ("ai2", Some(502))],
+        );
+        assert_eq!(result.sat_inscriptions[&100].inscriptions, vec!["ai0", "ai3", "ai4"]);
+    }
+}
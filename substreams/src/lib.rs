@@ -1,92 +1,151 @@
+// The handler macro reads module params from a raw pointer in the exported function,
+// which does not keep the attributes of the handler
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
 mod ord;
+mod runes;
 mod pb;
 mod address;
+mod decoded;
+mod error;
 mod sats_utils;
+mod sat_ranges;
+mod fees;
+mod numbering;
+mod inscriptions;
+mod transfers;
+mod rare_sats;
+mod runestones;
+mod etchings;
+mod mints;
+mod supplies;
+mod rune_balances;
+mod database;
+mod graph;
 
-use bitcoin::{consensus::deserialize, hashes::hex::FromHex, Transaction};
-use ord::envelope::ParsedEnvelope;
-use address::address_from_scriptpubkey;
-use pb::ordinals::v1::{self as ord_proto, Inscription};
+use ord::chain::Chain;
+use decoded::DecodedBlock;
+use pb::ordinals::v1::{
+    self as ord_proto, Etching, Inscription, RuneBalanceOperation, RuneTransaction, SatInscriptions, SatRanges,
+    SatSources, UtxoInscriptions, UtxoRuneBalances,
+};
 use pb::sf::bitcoin::r#type::v1 as btc;
 use anyhow::Result;
+use substreams::pb::substreams::store_delta::Operation;
+use substreams::store::{
+    Appender, DeltaInt64, DeltaProto, DeltaString, Deltas, StoreAdd, StoreAddBigInt, StoreAddInt64, StoreAppend,
+    StoreDelete, StoreGet, StoreGetBigInt, StoreGetInt64, StoreGetProto, StoreGetString, StoreNew, StoreSet,
+    StoreSetIfNotExists, StoreSetIfNotExistsInt64, StoreSetIfNotExistsString, StoreSetInt64, StoreSetProto,
+    StoreSetString,
+};
+use substreams_database_change::pb::database::DatabaseChanges;
+use substreams_entity_change::pb::entity::EntityChanges;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use substreams::scalar::BigInt;
+
+use sats_utils::subsidy;
+use sat_ranges::{assign_sat_ranges, assign_sat_sources, resolve_sat_ranges, BlockSatRanges};
+use fees::{fee_rate, transaction_fees, BlockFees};
+use numbering::{assign_inscription_numbers, BLESSED_KEY, CURSED_KEY};
+use inscriptions::{index_inscriptions, BlockInscriptions};
+use transfers::{inscribed_sat_bucket, transfer_inscriptions, utxo_inscriptions};
+use rare_sats::follow_rare_sats;
+use runestones::block_runestones;
+use etchings::validate_etchings;
+use mints::{accept_mints, attempt_mints, Mint};
+use supplies::{rune_supply, updated_runes};
+use rune_balances::{apply_runestones, rune_transactions, RuneHistory};
+use runes::{rune::Rune, rune_id::RuneId};
 
-use sats_utils::{btc_to_sats, subsidy, block_supply};
+#[substreams::handlers::map]
+fn map_ordinals(
+    block: ord_proto::RawBlock,
+    block_sat_ranges: ord_proto::BlockSatRanges,
+    utxo_values: Deltas<DeltaInt64>,
+    inscriptions: ord_proto::Inscriptions,
+) -> Result<ord_proto::Block, substreams::errors::Error> {
+    let block = DecodedBlock::from_raw(&block);
 
-impl btc::Transaction {
-    pub fn amount(&self) -> u64 {
-        self.vout.iter()
-            .map(|vout| btc_to_sats(vout.value))
-            .sum()
+    // Empty blocks are only reported, they do not hold anything to index
+    if block.txs.is_empty() {
+        return Ok(ord_proto::Block {
+            number: block.height,
+            timestamp: block.time,
+            diagnostics: block.diagnostics.0,
+            ..Default::default()
+        });
     }
-}
 
-#[substreams::handlers::map]
-fn map_ordinals(block: btc::Block) -> Result<ord_proto::Block, substreams::errors::Error> {
-    // Total supply of sats before the block is mined
-    let total_supply = if block.height == 0 {0} else {
-        block_supply((block.height - 1) as u64)
-    };
-    let block_subsidy = subsidy(block.height as u64);
+    let block_sat_ranges = BlockSatRanges::from(block_sat_ranges);
 
-    // First ordinal of the block subsidy
-    let first_ordinal = total_supply;
+    let mut spent_values = spent_values(utxo_values);
+    let BlockFees { fees, diagnostics: fee_diagnostics } = transaction_fees(&block, |utxo| spent_values.remove(utxo));
+
+    let mut diagnostics = block.diagnostics.clone();
+    diagnostics.append(block_sat_ranges.diagnostics.clone());
+    diagnostics.append(fee_diagnostics);
+
+    let block_subsidy = subsidy(block.height);
+
+    // Inscriptions are indexed and numbered by `map_inscriptions`
+    let mut tx_inscriptions: HashMap<String, Vec<Inscription>> = HashMap::new();
+    for inscription in inscriptions.inscriptions {
+        let txid = inscription.id.rsplit_once('i').map(|(txid, _)| txid.to_string()).unwrap_or_default();
+        tx_inscriptions.entry(txid).or_default().push(inscription);
+    }
 
     // Get coinbase tx
-    let raw_coinbase_tx = &block.tx[0];
+    let raw_coinbase_tx = &block.txs[0];
     let coinbase_tx = ord_proto::Transaction {
         txid: raw_coinbase_tx.txid.clone(),
         idx: 0,
         amount: raw_coinbase_tx.amount(),
-        coinbase_ordinals: raw_coinbase_tx.vout.iter()
-            .fold((first_ordinal, vec![]), |(counter, mut rel_ass), vout| {
-                rel_ass.push(ord_proto::OrdinalBlock {
-                    utxo: raw_coinbase_tx.txid.clone() + ":" + &vout.n.to_string(),
-                    address: address_from_scriptpubkey(&vout.script_pub_key.as_ref().unwrap().hex),
-                    start: counter,
-                    size: btc_to_sats(vout.value),
-                });
-                (counter + btc_to_sats(vout.value), rel_ass)
-            }).1,
+        // Subsidy sats first, then the fee sats of each transaction in block order
+        coinbase_ordinals: raw_coinbase_tx.output_values()
+            .zip(&block_sat_ranges.txs[0].outputs)
+            .enumerate()
+            .map(|(vout, (value, (utxo, ranges)))| ord_proto::OrdinalBlock {
+                utxo: utxo.clone(),
+                address: raw_coinbase_tx.output_address(vout, block.chain),
+                // First sat of the UTXO, the following ones are not necessarily contiguous
+                start: ranges.ranges.first().map(|range| range.start).unwrap_or_default(),
+                size: value,
+                absolute_ordinals: ranges.with_rarity(),
+            })
+            .collect(),
         input_utxos: vec![],
         relative_ordinals: vec![],
+        fee: 0,
+        fee_rate: 0.,
         // Might not be necessary, could set to empty vec
-        inscriptions: match parse_inscriptions(raw_coinbase_tx.clone()) {
-            Ok(inscriptions) => inscriptions,
-            Err(err) => {
-                substreams::log::info!("Error parsing inscriptions in tx {}: {}", raw_coinbase_tx.txid, err);
-                vec![]
-            }
-        }
+        inscriptions: tx_inscriptions.remove(&raw_coinbase_tx.txid).unwrap_or_default(),
     };
 
     // Handle non-coinbase transactions
-    let mut transactions = block.tx[1..].iter().enumerate().map(|(idx, tx)| {
+    let mut transactions = block.txs[1..].iter().enumerate().map(|(idx, tx)| {
         ord_proto::Transaction {
             txid: tx.txid.clone(),
-            idx: (idx + 1) as u64,
+            idx: tx.idx as u64,
             amount: tx.amount(),
-            // fee: 
+            fee: fees[idx],
+            fee_rate: fee_rate(fees[idx], tx.vsize),
             coinbase_ordinals: vec![],
-            input_utxos: tx.vin.iter()
-                .map(|vin| vin.txid.clone() + ":" + &vin.vout.to_string())
-                .collect(),
-            relative_ordinals: tx.vout.iter()
-                .fold((0, vec![]), |(counter, mut rel_ass), vout| {
+            input_utxos: tx.inputs().collect(),
+            relative_ordinals: tx.output_values()
+                .zip(&block_sat_ranges.txs[idx + 1].outputs)
+                .enumerate()
+                .fold((0, vec![]), |(counter, mut rel_ass), (vout, (value, (utxo, ranges)))| {
                     rel_ass.push(ord_proto::OrdinalBlock {
-                        utxo: tx.txid.clone() + ":" + &vout.n.to_string(),
-                        address: address_from_scriptpubkey(&vout.script_pub_key.as_ref().unwrap().hex),
+                        utxo: utxo.clone(),
+                        address: tx.output_address(vout, block.chain),
                         start: counter,
-                        size: btc_to_sats(vout.value),
+                        size: value,
+                        absolute_ordinals: ranges.with_rarity(),
                     });
-                    (counter + btc_to_sats(vout.value), rel_ass)
+                    (counter + value, rel_ass)
                 }).1,
-            inscriptions: match parse_inscriptions(tx.clone()) {
-                Ok(inscriptions) => inscriptions,
-                Err(err) => {
-                    substreams::log::info!("Error parsing inscriptions in tx {}: {}", tx.txid, err);
-                    vec![]
-                }
-            }
+            inscriptions: tx_inscriptions.remove(&tx.txid).unwrap_or_default(),
         }
     }).collect::<Vec<_>>();
 
@@ -94,74 +153,584 @@ fn map_ordinals(block: btc::Block) -> Result<ord_proto::Block, substreams::error
     let mut all_txs = vec![coinbase_tx];
     all_txs.append(&mut transactions);
     let block = ord_proto::Block {
-        number: block.height as u64,
-        timestamp: block.time as u64,
-        miner_reward: all_txs[0].amount.clone(),
+        number: block.height,
+        timestamp: block.time,
+        miner_reward: all_txs[0].amount,
         subsidy: block_subsidy as u64,
-        fees: all_txs[0].amount - block_subsidy,
+        // Sats left over by the block's transactions. Not derived from the miner reward
+        // since a miner can claim less than the subsidy and fees
+        fees: block_sat_ranges.txs.iter().map(|tx| tx.fee.value()).sum(),
         txs: all_txs,
+        diagnostics: diagnostics.0,
     };
 
     Ok(block)
 }
 
+/// Values of the UTXOs deleted from a store keyed by UTXO, i.e.: spent, in a block
+fn spent_utxos<T: prost::Message + Default + PartialEq>(deltas: Deltas<DeltaProto<T>>) -> HashMap<String, T> {
+    deltas.deltas.into_iter()
+        .filter(|delta| delta.operation == Operation::Delete)
+        .map(|delta| (utxo_from_key(&delta.key).to_string(), delta.old_value))
+        .collect()
+}
+
+/// Values of the UTXOs deleted from `store_utxo_values` or `store_taproot_utxo_heights`,
+/// i.e.: spent, in a block
+fn spent_values(deltas: Deltas<DeltaInt64>) -> HashMap<String, u64> {
+    deltas.deltas.into_iter()
+        .filter(|delta| delta.operation == Operation::Delete)
+        .map(|delta| (utxo_from_key(&delta.key).to_string(), delta.old_value as u64))
+        .collect()
+}
+
+/// Addresses of the UTXOs deleted from `store_rare_sat_addresses`, i.e.: spent, in a block
+fn spent_addresses(deltas: Deltas<DeltaString>) -> HashMap<String, String> {
+    deltas.deltas.into_iter()
+        .filter(|delta| delta.operation == Operation::Delete)
+        .map(|delta| (utxo_from_key(&delta.key).to_string(), delta.old_value))
+        .collect()
+}
+
+/// Runes etched in a block, recovered from the names created in `store_rune_ids`
+fn etched_runes(deltas: Deltas<DeltaString>) -> BTreeMap<RuneId, Rune> {
+    deltas.deltas.into_iter()
+        .filter(|delta| delta.operation == Operation::Create)
+        .filter_map(|delta| Some((delta.new_value.parse().ok()?, delta.key.parse().ok()?)))
+        .collect()
+}
+
+/// Mints accepted in a block by transaction index, as output by `map_rune_mints`
+fn accepted_mints(rune_mints: &ord_proto::RuneMints) -> BTreeMap<u32, Mint> {
+    rune_mints.mints.iter()
+        .filter_map(|mint| {
+            let id = mint.rune_id.parse().ok()?;
+            Some((mint.tx_index, Mint { id, amount: mint.amount.parse().ok()? }))
+        })
+        .collect()
+}
+
+/// Items of a value appended to an append store, each followed by `;`
+fn appended_items(value: Option<String>) -> Vec<String> {
+    value.unwrap_or_default()
+        .split(';')
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn utxo_from_key(key: &str) -> &str {
+    key.trim_end_matches('/')
+}
+
+/// Store key of a UTXO. Stores can only delete keys by prefix, so the key is
+/// terminated to make sure deleting `txid:1` does not delete `txid:10` as well.
+fn utxo_key(utxo: &str) -> String {
+    utxo.to_string() + "/"
+}
+
 #[substreams::handlers::map]
-fn map_transaction(block: btc::Block) -> Result<btc::Transaction, substreams::errors::Error> {
-    if let Some(tx) = block.tx.iter().last() {
-        Ok(tx.clone())
-    } else {
-        panic!("No transactions in block")
-    }
+fn map_decoded_block(params: String, block: btc::Block) -> Result<ord_proto::RawBlock, substreams::errors::Error> {
+    // Network indexed by the substreams, carried by the block to the other modules
+    let chain = params.parse::<Chain>()?;
+
+    // Decoded once, the other modules only deserialize the raw transactions
+    Ok(DecodedBlock::decode(&block, chain).encode())
 }
 
 #[substreams::handlers::map]
-fn map_inscriptions(block: btc::Block) -> Result<ord_proto::Inscriptions, substreams::errors::Error> {
-    let inscriptions = block.tx.into_iter()
-        .filter(|tx| tx.hex.contains("0063"))
-        .flat_map(|tx| {
-            let txid = tx.txid.clone();
-            match parse_inscriptions(tx) {
-                Ok(inscriptions) => inscriptions,
-                Err(err) => {
-                    substreams::log::info!("Error parsing inscriptions in tx {}: {}", txid, err);
-                    vec![]
-                }
+fn map_block_sat_ranges(
+    block: ord_proto::RawBlock,
+    utxo_sat_ranges: Deltas<DeltaProto<SatRanges>>,
+) -> Result<ord_proto::BlockSatRanges, substreams::errors::Error> {
+    let block = DecodedBlock::from_raw(&block);
+
+    // The store deltas hold the sat ranges of the UTXOs spent in this block,
+    // which is all that is needed to replay the assignment of the block
+    let mut spent_sat_ranges = spent_utxos(utxo_sat_ranges);
+
+    Ok(assign_sat_ranges(&block, |utxo| spent_sat_ranges.remove(utxo)).into())
+}
+
+#[substreams::handlers::store]
+fn store_utxo_sat_sources(
+    block: ord_proto::RawBlock,
+    utxo_values: Deltas<DeltaInt64>,
+    store: StoreSetProto<SatSources>,
+) {
+    let block = DecodedBlock::from_raw(&block);
+    let mut spent_values = spent_values(utxo_values);
+
+    // Sources are kept once their UTXO is spent, they are needed to resolve the UTXOs created from it
+    for (utxo, sources) in assign_sat_sources(&block, |utxo| spent_values.remove(utxo)) {
+        store.set(0, utxo_key(&utxo), &sources);
+    }
+}
+
+#[substreams::handlers::store]
+fn store_utxo_sat_ranges(
+    block: ord_proto::RawBlock,
+    utxo_sat_sources: StoreGetProto<SatSources>,
+    store: StoreSetProto<SatRanges>,
+) {
+    let block = DecodedBlock::from_raw(&block);
+
+    let block_sat_ranges = assign_sat_ranges(&block, |utxo| {
+        resolve_sat_ranges(utxo, |utxo| utxo_sat_sources.get_last(utxo_key(utxo)))
+    });
+
+    for tx in &block_sat_ranges.txs {
+        for (utxo, ranges) in &tx.outputs {
+            store.set(0, utxo_key(utxo), ranges);
+        }
+    }
+
+    // Deleted after all outputs are set since a UTXO can be created and spent in the same block
+    for tx in &block_sat_ranges.txs {
+        for (utxo, _) in &tx.inputs {
+            store.delete_prefix(0, &utxo_key(utxo));
+        }
+    }
+}
+
+#[substreams::handlers::store]
+fn store_utxo_values(block: ord_proto::RawBlock, store: StoreSetInt64) {
+    let block = DecodedBlock::from_raw(&block);
+
+    for tx in &block.txs {
+        for (vout, value) in tx.output_values().enumerate() {
+            store.set(0, utxo_key(&tx.utxo(vout)), &(value as i64));
+        }
+    }
+
+    for tx in block.txs.iter().skip(1) {
+        for utxo in tx.inputs() {
+            store.delete_prefix(0, &utxo_key(&utxo));
+        }
+    }
+}
+
+#[substreams::handlers::store]
+fn store_taproot_utxo_heights(block: ord_proto::RawBlock, store: StoreSetInt64) {
+    let block = DecodedBlock::from_raw(&block);
+
+    for tx in &block.txs {
+        for (vout, output) in tx.raw.output.iter().enumerate() {
+            if output.script_pubkey.is_p2tr() {
+                store.set(0, utxo_key(&tx.utxo(vout)), &(block.height as i64));
             }
-        })
-        .collect::<Vec<_>>();
+        }
+    }
+
+    for tx in block.txs.iter().skip(1) {
+        for utxo in tx.inputs() {
+            store.delete_prefix(0, &utxo_key(&utxo));
+        }
+    }
+}
+
+#[substreams::handlers::store]
+fn store_rune_ids(
+    block: ord_proto::RawBlock,
+    taproot_utxo_heights: Deltas<DeltaInt64>,
+    store: StoreSetIfNotExistsString,
+) {
+    let block = DecodedBlock::from_raw(&block);
+    let mut commit_heights = spent_values(taproot_utxo_heights);
+    let etched = validate_etchings(&block, |utxo| commit_heights.remove(utxo));
+
+    // Names already etched in a previous block are kept, the etching is then ignored
+    for (id, rune) in &etched {
+        store.set_if_not_exists(0, rune.to_string(), &id.to_string());
+    }
+}
+
+#[substreams::handlers::store]
+fn store_sat_inscriptions(
+    block: ord_proto::RawBlock,
+    block_sat_ranges: ord_proto::BlockSatRanges,
+    store: StoreAppend<String>,
+) {
+    let block = DecodedBlock::from_raw(&block);
+    let block_sat_ranges = BlockSatRanges::from(block_sat_ranges);
+
+    // The IDs and sats of the inscriptions do not depend on the previous inscriptions
+    for inscription in index_inscriptions(&block, &block_sat_ranges, |_| None).inscriptions {
+        if let Some(sat) = inscription.sat {
+            store.append(0, sat.to_string(), inscription.id);
+        }
+    }
+}
+
+#[substreams::handlers::store]
+fn store_first_inscription_curses(
+    block: ord_proto::RawBlock,
+    block_sat_ranges: ord_proto::BlockSatRanges,
+    store: StoreSetIfNotExistsInt64,
+) {
+    let block = DecodedBlock::from_raw(&block);
+    let block_sat_ranges = BlockSatRanges::from(block_sat_ranges);
+
+    // Only the first inscription of a sat is kept, its curse only depends on its envelope
+    // when the sat was not inscribed before
+    for inscription in index_inscriptions(&block, &block_sat_ranges, |_| None).inscriptions {
+        if let Some(sat) = inscription.sat {
+            let cursed_or_vindicated = inscription.curse != ord_proto::Curse::None as i32;
+            store.set_if_not_exists(0, sat.to_string(), &(cursed_or_vindicated as i64));
+        }
+    }
+}
+
+#[substreams::handlers::store]
+fn store_inscription_numbers(
+    block: ord_proto::RawBlock,
+    block_sat_ranges: ord_proto::BlockSatRanges,
+    sat_inscriptions: StoreGetString,
+    first_inscription_curses: StoreGetInt64,
+    store: StoreAddInt64,
+) {
+    let block = DecodedBlock::from_raw(&block);
+    let block_sat_ranges = BlockSatRanges::from(block_sat_ranges);
+    let block_inscriptions =
+        block_inscriptions(&block, &block_sat_ranges, &sat_inscriptions, &first_inscription_curses);
+    let (cursed, blessed): (Vec<_>, Vec<_>) = block_inscriptions.inscriptions
+        .into_iter()
+        .partition(|inscription| inscription.cursed);
+
+    store.add(0, BLESSED_KEY, blessed.len() as i64);
+    store.add(0, CURSED_KEY, cursed.len() as i64);
+}
+
+#[substreams::handlers::store]
+fn store_inscribed_sats(inscriptions: ord_proto::Inscriptions, store: StoreAppend<String>) {
+    for sat in inscriptions.inscriptions.iter().filter_map(|inscription| inscription.sat) {
+        store.append(0, inscribed_sat_bucket(sat).to_string(), sat.to_string());
+    }
+}
+
+#[substreams::handlers::store]
+fn store_utxo_inscriptions(
+    block: ord_proto::RawBlock,
+    block_sat_ranges: ord_proto::BlockSatRanges,
+    inscriptions: ord_proto::Inscriptions,
+    sat_inscriptions: StoreGetString,
+    inscribed_sats: StoreGetString,
+    store: StoreSetProto<UtxoInscriptions>,
+) {
+    let block = DecodedBlock::from_raw(&block);
+    let block_sat_ranges = BlockSatRanges::from(block_sat_ranges);
+    let spent_sat_ranges: HashMap<&str, &SatRanges> = block_sat_ranges.txs.iter()
+        .flat_map(|tx| &tx.inputs)
+        .map(|(utxo, ranges)| (utxo.as_str(), ranges))
+        .collect();
+
+    // The inscriptions held by the UTXOs spent in the block are found from the sats inscribed
+    // before the block, those revealed in the block being placed by `transfer_inscriptions`
+    let block_transfers = transfer_inscriptions(
+        &block,
+        &block_sat_ranges,
+        &inscriptions.inscriptions,
+        |utxo| {
+            utxo_inscriptions(
+                spent_sat_ranges.get(utxo)?,
+                |bucket| {
+                    appended_items(inscribed_sats.get_first(bucket.to_string()))
+                        .iter()
+                        .filter_map(|sat| sat.parse().ok())
+                        .collect()
+                },
+                |sat| appended_items(sat_inscriptions.get_first(sat.to_string())),
+            )
+        },
+    );
+
+    for (utxo, inscriptions) in &block_transfers.created {
+        store.set(0, utxo_key(utxo), inscriptions);
+    }
+
+    for utxo in &block_transfers.spent {
+        store.delete_prefix(0, &utxo_key(utxo));
+    }
+}
+
+#[substreams::handlers::store]
+fn store_rare_sat_addresses(
+    block: ord_proto::RawBlock,
+    block_sat_ranges: ord_proto::BlockSatRanges,
+    store: StoreSetString,
+) {
+    let block = DecodedBlock::from_raw(&block);
+    let block_sat_ranges = BlockSatRanges::from(block_sat_ranges);
+    // Only the UTXOs to create and delete are needed, not the addresses of the spent ones
+    let block_rare_sats = follow_rare_sats(&block, &block_sat_ranges, |_| None);
+
+    for (utxo, address) in &block_rare_sats.created {
+        if let Some(address) = address {
+            store.set(0, utxo_key(utxo), address);
+        }
+    }
+
+    for utxo in &block_rare_sats.spent {
+        store.delete_prefix(0, &utxo_key(utxo));
+    }
+}
+
+#[substreams::handlers::store]
+fn store_etchings(runestones: ord_proto::Runestones, store: StoreSetProto<Etching>) {
+    for etching in runestones.runestones.iter().filter_map(|runestone| runestone.etching.as_ref()) {
+        if etching.valid {
+            store.set(0, &etching.rune_id, etching);
+        }
+    }
+}
+
+#[substreams::handlers::store]
+fn store_rune_mint_attempts(block: ord_proto::RawBlock, etchings: StoreGetProto<Etching>, store: StoreAddInt64) {
+    let block = DecodedBlock::from_raw(&block);
+
+    // Mints are counted past the cap, whether they are accepted is left to `map_rune_mints`
+    for (mint, _) in attempt_mints(&block, |id| etchings.get_last(id.to_string())).values() {
+        store.add(0, mint.id.to_string(), 1);
+    }
+}
+
+#[substreams::handlers::store]
+fn store_rune_transactions(
+    block: ord_proto::RawBlock,
+    rune_ids: Deltas<DeltaString>,
+    rune_mints: ord_proto::RuneMints,
+    store: StoreSetProto<RuneTransaction>,
+) {
+    let block = DecodedBlock::from_raw(&block);
+
+    for (txid, record) in rune_transactions(&block, &etched_runes(rune_ids), &accepted_mints(&rune_mints)) {
+        store.set(0, txid, &record);
+    }
+}
+
+#[substreams::handlers::store]
+fn store_utxo_rune_balances(
+    block: ord_proto::RawBlock,
+    rune_ids: Deltas<DeltaString>,
+    rune_mints: ord_proto::RuneMints,
+    rune_transactions: StoreGetProto<RuneTransaction>,
+    store: StoreSetProto<UtxoRuneBalances>,
+) {
+    let block = DecodedBlock::from_raw(&block);
+
+    // The balances of the UTXOs spent in the block are replayed from the transactions they come from
+    let mut rune_history = RuneHistory::new(block.chain, |txid: &str| rune_transactions.get_last(txid));
+    let block_rune_balances = apply_runestones(
+        &block,
+        &etched_runes(rune_ids),
+        &accepted_mints(&rune_mints),
+        |utxo| rune_history.balances(utxo),
+    );
+
+    for (utxo, balances) in &block_rune_balances.created {
+        store.set(0, utxo_key(utxo), balances);
+    }
+
+    for utxo in &block_rune_balances.spent {
+        store.delete_prefix(0, &utxo_key(utxo));
+    }
+}
+
+#[substreams::handlers::store]
+fn store_rune_burns(rune_balances: ord_proto::RuneBalanceDeltas, store: StoreAddBigInt) {
+    for delta in &rune_balances.deltas {
+        if delta.operation != RuneBalanceOperation::Burned as i32 {
+            continue;
+        }
+        if let Ok(amount) = BigInt::from_str(&delta.amount) {
+            store.add(0, &delta.rune_id, amount);
+        }
+    }
+}
+
+#[substreams::handlers::map]
+fn map_transaction(block: btc::Block) -> Result<btc::Transaction, substreams::errors::Error> {
+    // Empty blocks are reported by `map_ordinals`, they do not have a last transaction
+    Ok(block.tx.last().cloned().unwrap_or_default())
+}
+
+#[substreams::handlers::map]
+fn map_inscriptions(
+    block: ord_proto::RawBlock,
+    block_sat_ranges: ord_proto::BlockSatRanges,
+    sat_inscriptions: StoreGetString,
+    first_inscription_curses: StoreGetInt64,
+    inscription_numbers: StoreGetInt64,
+) -> Result<ord_proto::Inscriptions, substreams::errors::Error> {
+    let block = DecodedBlock::from_raw(&block);
+    let block_sat_ranges = BlockSatRanges::from(block_sat_ranges);
+    let mut inscriptions =
+        block_inscriptions(&block, &block_sat_ranges, &sat_inscriptions, &first_inscription_curses).inscriptions;
+    assign_inscription_numbers(
+        &mut inscriptions,
+        inscription_numbers.get_last(BLESSED_KEY).unwrap_or_default(),
+        inscription_numbers.get_last(CURSED_KEY).unwrap_or_default(),
+    );
 
     Ok(ord_proto::Inscriptions { inscriptions })
 }
 
-fn parse_inscriptions(tx: btc::Transaction) -> Result<Vec<Inscription>> {
-    let raw_trx = Vec::from_hex(&tx.hex).unwrap();
-    let tx_: Transaction = deserialize(&raw_trx).unwrap();
-    let envelopes = ParsedEnvelope::from_transaction(&tx_);
-    let inscriptions = envelopes.into_iter()
-        .enumerate()
-        .filter_map(move |(idx, envelope)| {
-        Some(Inscription {
-            id: format!("{}i{}", tx.txid, idx),
-            content_type: envelope.payload.content_type().map(|s| s.to_string()),
-            content_length: envelope.payload.content_length().map(|s| s.to_string()).unwrap_or("0".into()),
-            pointer: envelope.payload.pointer().map(|ptr| ptr as i64),
-            parent: envelope.payload.parent().map(|parent| parent.to_string()),
-            metadata: envelope.payload.metadata.clone().map(|metadata| match String::from_utf8(metadata.clone()) {
-                Ok(metadata) => metadata,   
-                Err(_) => hex::encode(metadata)
-            }),
-            metaprotocol: envelope.payload.metaprotocol().map(|s| s.to_string()),
-            content_encoding: envelope.payload.content_encoding().map(|s| match String::from_utf8(s.as_ref().to_vec()) {
-                Ok(content_type) => content_type,
-                Err(_) => hex::encode(s.as_ref())
-            }),
-            content: match String::from_utf8(envelope.payload.body().unwrap_or_default().to_vec()) {
-                Ok(content) => content,
-                Err(_) => hex::encode(envelope.payload.body().unwrap_or_default())
-            }
+/// Inscriptions revealed in a block, in order. The inscriptions previously made on
+/// the sats inscribed in the block are read as they were before the block.
+fn block_inscriptions(
+    block: &DecodedBlock,
+    block_sat_ranges: &BlockSatRanges,
+    sat_inscriptions: &StoreGetString,
+    first_inscription_curses: &StoreGetInt64,
+) -> BlockInscriptions {
+    index_inscriptions(block, block_sat_ranges, |sat| {
+        let inscriptions = sat_inscriptions.get_first(sat.to_string())?;
+
+        Some(SatInscriptions {
+            first_cursed_or_vindicated: first_inscription_curses.get_first(sat.to_string()) == Some(1),
+            inscriptions: appended_items(Some(inscriptions)),
         })
     })
-    .collect();
+}
+
+#[substreams::handlers::map]
+fn map_inscription_transfers(
+    block: ord_proto::RawBlock,
+    block_sat_ranges: ord_proto::BlockSatRanges,
+    inscriptions: ord_proto::Inscriptions,
+    utxo_inscriptions: Deltas<DeltaProto<UtxoInscriptions>>,
+) -> Result<ord_proto::InscriptionTransfers, substreams::errors::Error> {
+    let block = DecodedBlock::from_raw(&block);
+    let block_sat_ranges = BlockSatRanges::from(block_sat_ranges);
+
+    // The store deltas hold the inscriptions of the inscribed UTXOs spent in this block
+    let mut spent_utxo_inscriptions = spent_utxos(utxo_inscriptions);
+    let block_transfers = transfer_inscriptions(
+        &block,
+        &block_sat_ranges,
+        &inscriptions.inscriptions,
+        |utxo| spent_utxo_inscriptions.remove(utxo),
+    );
+
+    Ok(ord_proto::InscriptionTransfers { transfers: block_transfers.transfers })
+}
+
+#[substreams::handlers::map]
+fn map_rare_sats(
+    block: ord_proto::RawBlock,
+    block_sat_ranges: ord_proto::BlockSatRanges,
+    rare_sat_addresses: Deltas<DeltaString>,
+) -> Result<ord_proto::RareSatMoves, substreams::errors::Error> {
+    let block = DecodedBlock::from_raw(&block);
+    let block_sat_ranges = BlockSatRanges::from(block_sat_ranges);
+
+    // The store deltas hold the addresses of the UTXOs holding rare sats spent in this block
+    let mut spent_addresses = spent_addresses(rare_sat_addresses);
+    let block_rare_sats = follow_rare_sats(&block, &block_sat_ranges, |utxo| spent_addresses.remove(utxo));
+
+    Ok(ord_proto::RareSatMoves { moves: block_rare_sats.moves })
+}
+
+#[substreams::handlers::map]
+fn map_runestones(
+    block: ord_proto::RawBlock,
+    rune_ids: Deltas<DeltaString>,
+) -> Result<ord_proto::Runestones, substreams::errors::Error> {
+    let block = DecodedBlock::from_raw(&block);
+
+    Ok(ord_proto::Runestones { runestones: block_runestones(&block, &etched_runes(rune_ids)) })
+}
+
+#[substreams::handlers::map]
+fn map_rune_mints(
+    block: ord_proto::RawBlock,
+    etchings: StoreGetProto<Etching>,
+    rune_mint_attempts: StoreGetInt64,
+) -> Result<ord_proto::RuneMints, substreams::errors::Error> {
+    let block = DecodedBlock::from_raw(&block);
+    let attempted = attempt_mints(&block, |id| etchings.get_last(id.to_string()));
+    let accepted = accept_mints(&attempted, |id| {
+        rune_mint_attempts.get_first(id.to_string()).unwrap_or_default() as u128
+    });
+
+    Ok(ord_proto::RuneMints {
+        mints: accepted.into_iter()
+            .map(|(tx_index, mint)| ord_proto::RuneMint {
+                rune_id: mint.id.to_string(),
+                tx_index,
+                amount: mint.amount.to_string(),
+            })
+            .collect(),
+    })
+}
+
+#[substreams::handlers::map]
+fn map_rune_balances(
+    block: ord_proto::RawBlock,
+    rune_ids: Deltas<DeltaString>,
+    rune_mints: ord_proto::RuneMints,
+    utxo_rune_balances: Deltas<DeltaProto<UtxoRuneBalances>>,
+) -> Result<ord_proto::RuneBalanceDeltas, substreams::errors::Error> {
+    let block = DecodedBlock::from_raw(&block);
+
+    // The store deltas hold the rune balances of the UTXOs holding runes spent in this block
+    let mut spent_rune_balances = spent_utxos(utxo_rune_balances);
+    let block_rune_balances = apply_runestones(
+        &block,
+        &etched_runes(rune_ids),
+        &accepted_mints(&rune_mints),
+        |utxo| spent_rune_balances.remove(utxo),
+    );
+
+    Ok(ord_proto::RuneBalanceDeltas { deltas: block_rune_balances.deltas })
+}
+
+#[substreams::handlers::map]
+fn map_rune_supplies(
+    block: ord_proto::RawBlock,
+    runestones: ord_proto::Runestones,
+    rune_mints: ord_proto::RuneMints,
+    rune_balances: ord_proto::RuneBalanceDeltas,
+    etchings: StoreGetProto<Etching>,
+    rune_mint_attempts: StoreGetInt64,
+    rune_burns: StoreGetBigInt,
+) -> Result<ord_proto::RuneSupplies, substreams::errors::Error> {
+    let updated = updated_runes(&runestones.runestones, &rune_mints.mints, &rune_balances.deltas);
+
+    // Supplies are rebuilt from the etching, mint attempts and burns of the runes up to this block
+    Ok(ord_proto::RuneSupplies {
+        supplies: updated.iter()
+            .map(|id| {
+                rune_supply(
+                    block.height,
+                    id,
+                    etchings.get_last(id).as_ref(),
+                    rune_mint_attempts.get_last(id).unwrap_or_default() as u64,
+                    rune_burns.get_last(id).and_then(|burned| burned.to_string().parse().ok()).unwrap_or_default(),
+                )
+            })
+            .collect(),
+    })
+}
+
+#[substreams::handlers::map]
+fn db_out(
+    block: ord_proto::Block,
+    transfers: ord_proto::InscriptionTransfers,
+) -> Result<DatabaseChanges, substreams::errors::Error> {
+    let mut tables = substreams_database_change::tables::Tables::new();
+    database::block_changes(&mut tables, &block, &transfers);
+
+    Ok(tables.to_database_changes())
+}
+
+#[substreams::handlers::map]
+fn graph_out(
+    block: ord_proto::Block,
+    transfers: ord_proto::InscriptionTransfers,
+) -> Result<EntityChanges, substreams::errors::Error> {
+    let mut tables = substreams_entity_change::tables::Tables::new();
+    graph::block_entity_changes(&mut tables, &block, &transfers);
 
-    Ok(inscriptions)
+    Ok(tables.to_entity_changes())
 }
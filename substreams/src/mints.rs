@@ -0,0 +1,211 @@
+use std::collections::BTreeMap;
+
+use crate::decoded::DecodedBlock;
+use crate::pb::ordinals::v1 as ord_proto;
+use crate::runes::etching::Terms;
+use crate::runes::rune_id::RuneId;
+use crate::runes::runestone::Runestone;
+
+/// Runes created by an accepted mint
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mint {
+    pub id: RuneId,
+    pub amount: u128,
+}
+
+/// Mints attempted by the transactions of `block`, by transaction index, along with
+/// the cap of mints of their rune. A mint is attempted if its rune was etched by a
+/// previous transaction with terms, and the block is within the height and offset
+/// windows of the terms. Cenotaphs mint too, the runes minted are burned.
+///
+/// `etching` returns the valid etchings by rune ID.
+pub fn attempt_mints<F>(block: &DecodedBlock, mut etching: F) -> BTreeMap<u32, (Mint, u128)>
+where
+    F: FnMut(RuneId) -> Option<ord_proto::Etching>,
+{
+    let mut attempted = BTreeMap::new();
+
+    if block.height < block.chain.first_rune_height() {
+        return attempted;
+    }
+
+    for tx in &block.txs {
+        let id = match Runestone::decipher(&tx.raw).and_then(|artifact| artifact.mint()) {
+            Some(id) => id,
+            None => continue,
+        };
+
+        // Runes etched later in the block, including by the minting transaction
+        // itself, cannot be minted yet
+        if (id.block, id.tx) >= (block.height, tx.idx) {
+            continue;
+        }
+
+        let terms = match etching(id).and_then(|etching| etching.terms).and_then(|terms| parse_terms(&terms)) {
+            Some(terms) => terms,
+            None => continue,
+        };
+
+        let (start, end) = window(id, &terms);
+        if matches!(start, Some(start) if block.height < start) || matches!(end, Some(end) if block.height >= end) {
+            continue;
+        }
+
+        let mint = Mint { id, amount: terms.amount.unwrap_or_default() };
+        attempted.insert(tx.idx, (mint, terms.cap.unwrap_or_default()));
+    }
+
+    attempted
+}
+
+/// Mints of a block accepted among the `attempted` ones, by transaction index: the
+/// first `cap` mints attempted of a rune are accepted. The mints attempted past the
+/// cap are never accepted, so this is the same as accepting a mint if fewer than
+/// `cap` mints were accepted before.
+///
+/// `attempted_before` returns the number of mints of a rune attempted in previous blocks.
+pub fn accept_mints<F>(attempted: &BTreeMap<u32, (Mint, u128)>, mut attempted_before: F) -> BTreeMap<u32, Mint>
+where
+    F: FnMut(RuneId) -> u128,
+{
+    let mut accepted = BTreeMap::new();
+
+    // Mints attempted so far, by rune ID
+    let mut attempts = BTreeMap::<RuneId, u128>::new();
+
+    for (&idx, &(mint, cap)) in attempted {
+        let count = attempts.entry(mint.id).or_insert_with(|| attempted_before(mint.id));
+        if *count < cap {
+            accepted.insert(idx, mint);
+        }
+        *count = count.saturating_add(1);
+    }
+
+    accepted
+}
+
+/// First height and height after the last one at which rune `id` can be minted, if
+/// any. The absolute `height` and the etching relative `offset` windows both apply.
+pub fn window(id: RuneId, terms: &Terms) -> (Option<u64>, Option<u64>) {
+    let relative = |offset: Option<u64>| offset.map(|offset| id.block.saturating_add(offset));
+
+    let start = match (relative(terms.offset.0), terms.height.0) {
+        (Some(relative), Some(absolute)) => Some(relative.max(absolute)),
+        (relative, absolute) => relative.or(absolute),
+    };
+    let end = match (relative(terms.offset.1), terms.height.1) {
+        (Some(relative), Some(absolute)) => Some(relative.min(absolute)),
+        (relative, absolute) => relative.or(absolute),
+    };
+
+    (start, end)
+}
+
+/// Terms of an etching output by `map_runestones`, `None` if an amount is malformed
+pub fn parse_terms(terms: &ord_proto::MintTerms) -> Option<Terms> {
+    let parse = |amount: &Option<String>| amount.as_deref().map(str::parse).transpose().ok();
+
+    Some(Terms {
+        amount: parse(&terms.amount)?,
+        cap: parse(&terms.cap)?,
+        height: (terms.height_start, terms.height_end),
+        offset: (terms.offset_start, terms.offset_end),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoded::test_utils::{block, tx};
+    use crate::runes::test_utils::runestone_tx;
+
+    const HEIGHT: u64 = 840_100;
+    const RUNE: RuneId = RuneId { block: 840_000, tx: 1 };
+
+    fn etching(id: RuneId, terms: ord_proto::MintTerms) -> ord_proto::Etching {
+        ord_proto::Etching {
+            rune_id: id.to_string(),
+            terms: Some(terms),
+            valid: true,
+            ..Default::default()
+        }
+    }
+
+    fn terms(amount: u128, cap: u128) -> ord_proto::MintTerms {
+        ord_proto::MintTerms {
+            amount: Some(amount.to_string()),
+            cap: Some(cap.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_accept_mints() {
+        let other = RuneId { block: 840_000, tx: 2 };
+        let unmintable = RuneId { block: 840_000, tx: 3 };
+        let etched = RuneId { block: HEIGHT, tx: 4 };
+        let block = block(HEIGHT, vec![
+            tx("cb", &[], &[]),
+            runestone_tx("a", &[("prev", 0)], &[20, RUNE.block.into(), 20, RUNE.tx.into()], 1),
+            runestone_tx("b", &[("prev", 0)], &[20, other.block.into(), 20, other.tx.into()], 1),
+            runestone_tx("c", &[("prev", 0)], &[20, unmintable.block.into(), 20, unmintable.tx.into()], 1),
+            // Mints the rune it etches, before it is etched
+            runestone_tx("d", &[("prev", 0)], &[20, etched.block.into(), 20, etched.tx.into(), 2, 1], 1),
+            // Cenotaph, the mint is accepted but the runes are burned
+            runestone_tx("e", &[("prev", 0)], &[20, RUNE.block.into(), 20, RUNE.tx.into(), 24, 1], 1),
+            // Over the cap of 3
+            runestone_tx("f", &[("prev", 0)], &[20, RUNE.block.into(), 20, RUNE.tx.into()], 1),
+            runestone_tx("g", &[("prev", 0)], &[20, etched.block.into(), 20, etched.tx.into()], 1),
+            runestone_tx("h", &[("prev", 0)], &[20, 1, 20, 0], 1),
+        ]);
+
+        let etchings = BTreeMap::from([
+            (RUNE, etching(RUNE, terms(100, 3))),
+            (other, etching(other, terms(5, 10))),
+            (unmintable, etching(unmintable, Default::default())),
+            (etched, etching(etched, terms(7, 1))),
+        ]);
+        // The cap of `other` was reached, more mints were attempted since
+        let attempts = BTreeMap::from([(RUNE, 1), (other, 12)]);
+
+        let attempted = attempt_mints(&block, |id| etchings.get(&id).cloned());
+        // Runes without a cap are attempted but never accepted
+        assert_eq!(attempted.keys().copied().collect::<Vec<_>>(), vec![1, 2, 3, 5, 6, 7]);
+
+        let accepted = accept_mints(&attempted, |id| attempts.get(&id).copied().unwrap_or_default());
+        assert_eq!(accepted, BTreeMap::from([
+            (1, Mint { id: RUNE, amount: 100 }),
+            (5, Mint { id: RUNE, amount: 100 }),
+            (7, Mint { id: etched, amount: 7 }),
+        ]));
+    }
+
+    #[test]
+    fn test_mint_windows() {
+        let accepted = |terms: ord_proto::MintTerms| {
+            let block = block(HEIGHT, vec![
+                tx("cb", &[], &[]),
+                runestone_tx("a", &[("prev", 0)], &[20, RUNE.block.into(), 20, RUNE.tx.into()], 1),
+            ]);
+            !attempt_mints(&block, |id| Some(etching(id, terms.clone()))).is_empty()
+        };
+        let window = |height: (Option<u64>, Option<u64>), offset: (Option<u64>, Option<u64>)| ord_proto::MintTerms {
+            height_start: height.0,
+            height_end: height.1,
+            offset_start: offset.0,
+            offset_end: offset.1,
+            ..terms(1, 1)
+        };
+
+        assert!(accepted(window((None, None), (None, None))));
+        assert!(accepted(window((Some(HEIGHT), Some(HEIGHT + 1)), (None, None))));
+        assert!(!accepted(window((Some(HEIGHT + 1), None), (None, None))));
+        assert!(!accepted(window((None, Some(HEIGHT)), (None, None))));
+        assert!(accepted(window((None, None), (Some(100), Some(101)))));
+        assert!(!accepted(window((None, None), (Some(101), None))));
+        assert!(!accepted(window((None, None), (None, Some(100)))));
+        // Both windows apply
+        assert!(!accepted(window((Some(HEIGHT), None), (None, Some(100)))));
+        assert!(!accepted(window((None, Some(HEIGHT + 1)), (Some(101), None))));
+    }
+}
@@ -0,0 +1,67 @@
+use crate::pb::ordinals::v1::Inscription;
+
+/// `store_inscription_numbers` key holding the number of blessed inscriptions
+pub const BLESSED_KEY: &str = "blessed";
+/// `store_inscription_numbers` key holding the number of cursed inscriptions
+pub const CURSED_KEY: &str = "cursed";
+
+/// Assigns numbers to the inscriptions revealed in a block, given in the order
+/// `ord` numbers them (as returned by `index_inscriptions`): blessed inscriptions
+/// are numbered upwards from 0 and cursed ones downwards from -1. `blessed_count` and `cursed_count` are the number of blessed and cursed
+/// inscriptions revealed up to and including the block.
+pub fn assign_inscription_numbers<'a, I>(inscriptions: I, blessed_count: i64, cursed_count: i64)
+where
+    I: IntoIterator<Item = &'a mut Inscription>,
+{
+    let mut inscriptions = inscriptions.into_iter().collect::<Vec<_>>();
+
+    let block_cursed = inscriptions.iter().filter(|inscription| inscription.cursed).count() as i64;
+    let block_blessed = inscriptions.len() as i64 - block_cursed;
+
+    let mut next_blessed = blessed_count - block_blessed;
+    let mut next_cursed = -(cursed_count - block_cursed) - 1;
+
+    for inscription in inscriptions.iter_mut() {
+        if inscription.cursed {
+            inscription.number = next_cursed;
+            next_cursed -= 1;
+        } else {
+            inscription.number = next_blessed;
+            next_blessed += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assign_inscription_numbers() {
+        let mut inscriptions = [false, true, false, true, false]
+            .iter()
+            .map(|&cursed| Inscription { cursed, ..Default::default() })
+            .collect::<Vec<_>>();
+
+        // 7 blessed and 4 cursed inscriptions before the block
+        assign_inscription_numbers(&mut inscriptions, 10, 6);
+
+        assert_eq!(
+            inscriptions.iter().map(|inscription| inscription.number).collect::<Vec<_>>(),
+            vec![7, -5, 8, -6, 9],
+        );
+    }
+
+    #[test]
+    fn test_assign_first_inscription_numbers() {
+        let mut inscriptions = vec![
+            Inscription { cursed: false, ..Default::default() },
+            Inscription { cursed: true, ..Default::default() },
+        ];
+
+        assign_inscription_numbers(&mut inscriptions, 1, 1);
+
+        assert_eq!(inscriptions[0].number, 0);
+        assert_eq!(inscriptions[1].number, -1);
+    }
+}
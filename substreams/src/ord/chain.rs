@@ -0,0 +1,122 @@
+use std::{str::FromStr, fmt::{Display, Formatter, self}};
+
+use bitcoin::Network;
+
+/// Consensus parameters of the networks supported by `ord`
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+pub enum Chain {
+  #[default]
+  Mainnet,
+  Regtest,
+  Signet,
+  Testnet,
+}
+
+impl Chain {
+  pub(crate) fn network(self) -> Network {
+    match self {
+      Self::Mainnet => Network::Bitcoin,
+      Self::Regtest => Network::Regtest,
+      Self::Signet => Network::Signet,
+      Self::Testnet => Network::Testnet,
+    }
+  }
+
+  /// Height of the first block containing inscriptions. Envelopes in earlier
+  /// blocks are not inscriptions.
+  pub(crate) fn first_inscription_height(self) -> u64 {
+    match self {
+      Self::Mainnet => 767430,
+      Self::Regtest => 0,
+      Self::Signet => 112402,
+      Self::Testnet => 2413343,
+    }
+  }
+
+  /// Height from which cursed inscriptions are no longer cursed and get
+  /// positive ("vindicated") inscription numbers
+  pub(crate) fn jubilee_height(self) -> u64 {
+    match self {
+      Self::Mainnet => 824544,
+      Self::Regtest => 110,
+      Self::Signet => 175392,
+      Self::Testnet => 2544192,
+    }
+  }
+
+  pub(crate) fn is_jubilant(self, height: u64) -> bool {
+    height >= self.jubilee_height()
+  }
+
+  /// Height of the first block whose runestones are indexed
+  pub(crate) fn first_rune_height(self) -> u64 {
+    match self {
+      Self::Mainnet => 840000,
+      Self::Regtest => 0,
+      Self::Signet => 0,
+      Self::Testnet => 2520000,
+    }
+  }
+}
+
+impl Display for Chain {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Self::Mainnet => "mainnet",
+        Self::Regtest => "regtest",
+        Self::Signet => "signet",
+        Self::Testnet => "testnet",
+      }
+    )
+  }
+}
+
+#[derive(Debug)]
+pub struct ParseError(String);
+
+impl Display for ParseError {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "invalid chain: `{}`", self.0)
+  }
+}
+
+impl std::error::Error for ParseError {}
+
+impl FromStr for Chain {
+  type Err = ParseError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "mainnet" => Ok(Self::Mainnet),
+      "regtest" => Ok(Self::Regtest),
+      "signet" => Ok(Self::Signet),
+      "testnet" => Ok(Self::Testnet),
+      _ => Err(ParseError(s.into())),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn jubilee() {
+    assert!(!Chain::Mainnet.is_jubilant(824543));
+    assert!(Chain::Mainnet.is_jubilant(824544));
+    assert!(!Chain::Testnet.is_jubilant(2544191));
+    assert!(Chain::Testnet.is_jubilant(2544192));
+    assert!(Chain::Regtest.is_jubilant(110));
+  }
+
+  #[test]
+  fn from_str() {
+    for chain in [Chain::Mainnet, Chain::Regtest, Chain::Signet, Chain::Testnet] {
+      assert_eq!(chain.to_string().parse::<Chain>().unwrap(), chain);
+    }
+    assert_eq!("bitcoin".parse::<Chain>().unwrap_err().to_string(), "invalid chain: `bitcoin`");
+  }
+}
@@ -0,0 +1,141 @@
+use super::envelope::ParsedEnvelope;
+use crate::pb::ordinals::v1 as ord_proto;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Curse {
+  DuplicateField,
+  IncompleteField,
+  NotAtOffsetZero,
+  NotInFirstInput,
+  Pointer,
+  Pushnum,
+  Reinscription,
+  Stutter,
+  UnrecognizedEvenField,
+}
+
+impl Curse {
+  /// Curse of an envelope, checked in the same order as `ord`. Reinscriptions
+  /// depend on the sats already inscribed and are not detected here.
+  pub(crate) fn from_envelope(envelope: &ParsedEnvelope) -> Option<Self> {
+    if envelope.payload.unrecognized_even_field {
+      Some(Self::UnrecognizedEvenField)
+    } else if envelope.payload.duplicate_field {
+      Some(Self::DuplicateField)
+    } else if envelope.payload.incomplete_field {
+      Some(Self::IncompleteField)
+    } else if envelope.input != 0 {
+      Some(Self::NotInFirstInput)
+    } else if envelope.offset != 0 {
+      Some(Self::NotAtOffsetZero)
+    } else if envelope.payload.pointer.is_some() {
+      Some(Self::Pointer)
+    } else if envelope.pushnum {
+      Some(Self::Pushnum)
+    } else if envelope.stutter {
+      Some(Self::Stutter)
+    } else {
+      None
+    }
+  }
+}
+
+impl From<Curse> for ord_proto::Curse {
+  fn from(curse: Curse) -> Self {
+    match curse {
+      Curse::DuplicateField => Self::DuplicateField,
+      Curse::IncompleteField => Self::IncompleteField,
+      Curse::NotAtOffsetZero => Self::NotAtOffsetZero,
+      Curse::NotInFirstInput => Self::NotInFirstInput,
+      Curse::Pointer => Self::Pointer,
+      Curse::Pushnum => Self::Pushnum,
+      Curse::Reinscription => Self::Reinscription,
+      Curse::Stutter => Self::Stutter,
+      Curse::UnrecognizedEvenField => Self::UnrecognizedEvenField,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ord::inscription::Inscription;
+
+  fn envelope(payload: Inscription) -> ParsedEnvelope {
+    ParsedEnvelope {
+      payload,
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn blessed() {
+    assert_eq!(
+      Curse::from_envelope(&envelope(Inscription::new(Some(b"text/plain".to_vec()), Some(b"ord".to_vec())))),
+      None
+    );
+  }
+
+  #[test]
+  fn curses() {
+    assert_eq!(
+      Curse::from_envelope(&envelope(Inscription {
+        unrecognized_even_field: true,
+        duplicate_field: true,
+        ..Default::default()
+      })),
+      Some(Curse::UnrecognizedEvenField)
+    );
+    assert_eq!(
+      Curse::from_envelope(&envelope(Inscription {
+        duplicate_field: true,
+        ..Default::default()
+      })),
+      Some(Curse::DuplicateField)
+    );
+    assert_eq!(
+      Curse::from_envelope(&envelope(Inscription {
+        incomplete_field: true,
+        ..Default::default()
+      })),
+      Some(Curse::IncompleteField)
+    );
+    assert_eq!(
+      Curse::from_envelope(&ParsedEnvelope {
+        input: 1,
+        offset: 1,
+        ..Default::default()
+      }),
+      Some(Curse::NotInFirstInput)
+    );
+    assert_eq!(
+      Curse::from_envelope(&ParsedEnvelope {
+        offset: 1,
+        ..Default::default()
+      }),
+      Some(Curse::NotAtOffsetZero)
+    );
+    assert_eq!(
+      Curse::from_envelope(&envelope(Inscription {
+        pointer: Some(Inscription::pointer_value(1)),
+        ..Default::default()
+      })),
+      Some(Curse::Pointer)
+    );
+    assert_eq!(
+      Curse::from_envelope(&ParsedEnvelope {
+        pushnum: true,
+        stutter: true,
+        ..Default::default()
+      }),
+      Some(Curse::Pushnum)
+    );
+    assert_eq!(
+      Curse::from_envelope(&ParsedEnvelope {
+        stutter: true,
+        ..Default::default()
+      }),
+      Some(Curse::Stutter)
+    );
+  }
+}
@@ -19,6 +19,7 @@ use {
 
 pub(crate) const PROTOCOL_ID: [u8; 3] = *b"ord";
 
+#[cfg(test)]
 pub(crate) const BODY_TAG: [u8; 0] = [];
 pub(crate) const CONTENT_TYPE_TAG: [u8; 1] = [1];
 pub(crate) const POINTER_TAG: [u8; 1] = [2];
@@ -69,6 +70,17 @@ fn remove_and_concatenate_field(
   }
 }
 
+/// Tapscripts revealed by the inputs of `transaction`, along with the index of
+/// their input. Having a tapscript does not mean the spent output is a taproot
+/// one: this is up to the callers to check.
+pub(crate) fn tapscripts(transaction: &Transaction) -> impl Iterator<Item = (usize, &Script)> {
+  transaction
+    .input
+    .iter()
+    .enumerate()
+    .filter_map(|(i, input)| Some((i, input.witness.tapscript()?)))
+}
+
 impl From<RawEnvelope> for ParsedEnvelope {
   fn from(envelope: RawEnvelope) -> Self {
     let body = envelope
@@ -141,11 +153,9 @@ impl RawEnvelope {
   pub(crate) fn from_transaction(transaction: &Transaction) -> Vec<Self> {
     let mut envelopes = Vec::new();
 
-    for (i, input) in transaction.input.iter().enumerate() {
-      if let Some(tapscript) = input.witness.tapscript() {
-        if let Ok(input_envelopes) = Self::from_tapscript(tapscript, i) {
-          envelopes.extend(input_envelopes);
-        }
+    for (i, tapscript) in tapscripts(transaction) {
+      if let Ok(input_envelopes) = Self::from_tapscript(tapscript, i) {
+        envelopes.extend(input_envelopes);
       }
     }
 
@@ -2,6 +2,13 @@ use bitcoin::{Txid, hashes::Hash};
 
 use super::inscription_id::InscriptionId;
 
+use {
+  std::str,
+  http::header::HeaderValue,
+};
+
+// Only used to build reveal scripts in tests
+#[cfg(test)]
 use {
   super::*,
   bitcoin::{
@@ -11,8 +18,6 @@ use {
     },
     ScriptBuf,
   },
-  std::str,
-  http::header::HeaderValue,
 };
 
 #[derive(Debug, PartialEq, Clone, Eq, Default)]
@@ -39,6 +44,7 @@ impl Inscription {
     }
   }
 
+  #[cfg(test)]
   pub(crate) fn pointer_value(pointer: u64) -> Vec<u8> {
     let mut bytes = pointer.to_le_bytes().to_vec();
 
@@ -49,6 +55,7 @@ impl Inscription {
     bytes
   }
 
+  #[cfg(test)]
   pub(crate) fn append_reveal_script_to_builder(
     &self,
     mut builder: script::Builder,
@@ -106,10 +113,6 @@ impl Inscription {
   }
 
   #[cfg(test)]
-  pub(crate) fn append_reveal_script(&self, builder: script::Builder) -> ScriptBuf {
-    self.append_reveal_script_to_builder(builder).into_script()
-  }
-
   pub(crate) fn append_batch_reveal_script_to_builder(
     inscriptions: &[Inscription],
     mut builder: script::Builder,
@@ -121,6 +124,7 @@ impl Inscription {
     builder
   }
 
+  #[cfg(test)]
   pub(crate) fn append_batch_reveal_script(
     inscriptions: &[Inscription],
     builder: script::Builder,
@@ -128,15 +132,10 @@ impl Inscription {
     Inscription::append_batch_reveal_script_to_builder(inscriptions, builder).into_script()
   }
 
-
   pub(crate) fn body(&self) -> Option<&[u8]> {
     Some(self.body.as_ref()?)
   }
 
-  pub(crate) fn into_body(self) -> Option<Vec<u8>> {
-    self.body
-  }
-
   pub(crate) fn content_length(&self) -> Option<usize> {
     Some(self.body()?.len())
   }
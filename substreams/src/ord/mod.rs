@@ -1,3 +1,5 @@
+pub mod chain;
+pub mod curse;
 pub mod envelope;
 pub mod inscription;
 pub mod inscription_id;
@@ -11,6 +11,112 @@ pub struct OrdinalBlock {
     pub start: u64,
     #[prost(uint64, tag="4")]
     pub size: u64,
+    /// Absolute sat ranges assigned to the UTXO
+    #[prost(message, repeated, tag="5")]
+    pub absolute_ordinals: ::prost::alloc::vec::Vec<SatRange>,
+}
+/// Represents a continuous range of sats
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SatRange {
+    #[prost(uint64, tag="1")]
+    pub start: u64,
+    #[prost(uint64, tag="2")]
+    pub size: u64,
+    /// Rarity of the first sat of the range (only set in emitted ranges)
+    #[prost(enumeration="Rarity", tag="3")]
+    pub rarity: i32,
+}
+/// Sat ranges held by a given UTXO, in order
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SatRanges {
+    #[prost(message, repeated, tag="1")]
+    pub ranges: ::prost::alloc::vec::Vec<SatRange>,
+}
+/// Sats taken from a spent UTXO, or mined when `utxo` is empty
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SatSource {
+    #[prost(string, tag="1")]
+    pub utxo: ::prost::alloc::string::String,
+    /// Offset of the first sat within the UTXO, or first sat of the mined range
+    #[prost(uint64, tag="2")]
+    pub start: u64,
+    #[prost(uint64, tag="3")]
+    pub size: u64,
+}
+/// Sources of the sats held by a given UTXO, in order
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SatSources {
+    #[prost(message, repeated, tag="1")]
+    pub sources: ::prost::alloc::vec::Vec<SatSource>,
+}
+/// Sat ranges held by a given UTXO
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UtxoSatRanges {
+    #[prost(string, tag="1")]
+    pub utxo: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="2")]
+    pub ranges: ::core::option::Option<SatRanges>,
+}
+/// Sat ranges moved by a transaction
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TransactionSatRanges {
+    /// Spent UTXOs and the sat ranges they held
+    #[prost(message, repeated, tag="1")]
+    pub inputs: ::prost::alloc::vec::Vec<UtxoSatRanges>,
+    /// Created UTXOs and the sat ranges assigned to them
+    #[prost(message, repeated, tag="2")]
+    pub outputs: ::prost::alloc::vec::Vec<UtxoSatRanges>,
+    /// Sats left over once the outputs are filled, paid as fee to the coinbase
+    #[prost(message, optional, tag="3")]
+    pub fee: ::core::option::Option<SatRanges>,
+}
+/// Sat ranges moved by the transactions of a block, in block order
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BlockSatRanges {
+    #[prost(message, repeated, tag="1")]
+    pub txs: ::prost::alloc::vec::Vec<TransactionSatRanges>,
+    /// UTXOs spent without known sat ranges
+    #[prost(message, repeated, tag="2")]
+    pub diagnostics: ::prost::alloc::vec::Vec<Diagnostic>,
+}
+/// Transaction decoded from its hex, consensus encoded
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RawTransaction {
+    #[prost(string, tag="1")]
+    pub txid: ::prost::alloc::string::String,
+    /// Index of the transaction in its block, undecodable transactions included
+    #[prost(uint32, tag="2")]
+    pub idx: u32,
+    #[prost(int32, tag="3")]
+    pub vsize: i32,
+    #[prost(bytes="vec", tag="4")]
+    pub raw: ::prost::alloc::vec::Vec<u8>,
+}
+/// Block whose transactions are decoded once, to be shared by all the modules
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RawBlock {
+    #[prost(uint64, tag="1")]
+    pub height: u64,
+    #[prost(uint64, tag="2")]
+    pub time: u64,
+    /// Decoded transactions, starting with the coinbase
+    #[prost(message, repeated, tag="3")]
+    pub txs: ::prost::alloc::vec::Vec<RawTransaction>,
+    /// Transactions which could not be decoded
+    #[prost(message, repeated, tag="4")]
+    pub diagnostics: ::prost::alloc::vec::Vec<Diagnostic>,
+    /// Network of the block (mainnet, regtest, signet or testnet), from the `map_decoded_block` param
+    #[prost(string, tag="5")]
+    pub chain: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -23,9 +129,9 @@ pub struct Transaction {
     /// Amount transferred in sats
     #[prost(uint64, tag="3")]
     pub amount: u64,
-    /// Fee in sats
-    /// int64 fee = 4;
     /// Ordinals assignment (only present for coinbase transaction)
+    /// The subsidy sats come first, followed by the fee sats of
+    /// each transaction in block order
     #[prost(message, repeated, tag="4")]
     pub coinbase_ordinals: ::prost::alloc::vec::Vec<OrdinalBlock>,
     /// Input UTXOs
@@ -35,10 +141,17 @@ pub struct Transaction {
     /// ordinals assigned to the input UTXOs
     /// E.g.: The Nth to Mth ordinals of the input utxos should
     /// be assigned to some output utxo
+    /// The sats actually assigned are in each block's `absolute_ordinals`
     #[prost(message, repeated, tag="6")]
     pub relative_ordinals: ::prost::alloc::vec::Vec<OrdinalBlock>,
     #[prost(message, repeated, tag="7")]
     pub inscriptions: ::prost::alloc::vec::Vec<Inscription>,
+    /// Fee in sats (zero for the coinbase transaction)
+    #[prost(uint64, tag="8")]
+    pub fee: u64,
+    /// Fee rate in sat/vB
+    #[prost(double, tag="9")]
+    pub fee_rate: f64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -61,6 +174,21 @@ pub struct Block {
     /// Block transactions
     #[prost(message, repeated, tag="6")]
     pub txs: ::prost::alloc::vec::Vec<Transaction>,
+    /// Malformed records skipped or worked around while indexing the block
+    #[prost(message, repeated, tag="7")]
+    pub diagnostics: ::prost::alloc::vec::Vec<Diagnostic>,
+}
+/// Malformed record found while indexing a block
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Diagnostic {
+    #[prost(enumeration="DiagnosticKind", tag="1")]
+    pub kind: i32,
+    /// Transaction holding the record, empty for block level records
+    #[prost(string, tag="2")]
+    pub txid: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub message: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -87,6 +215,34 @@ pub struct Inscription {
     pub content: ::prost::alloc::string::String,
     #[prost(string, tag="9")]
     pub content_length: ::prost::alloc::string::String,
+    /// Inscription number: positive for blessed inscriptions,
+    /// negative for cursed ones
+    #[prost(int64, tag="10")]
+    pub number: i64,
+    #[prost(bool, tag="11")]
+    pub cursed: bool,
+    /// Reason the inscription is cursed
+    #[prost(enumeration="Curse", tag="12")]
+    pub curse: i32,
+    /// Whether the inscription would have been cursed before the jubilee
+    #[prost(bool, tag="13")]
+    pub vindicated: bool,
+    /// Inscriptions previously made on the same sat, in order
+    #[prost(string, repeated, tag="14")]
+    pub reinscription_of: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Sat the inscription is made on, unset for unbound inscriptions
+    #[prost(uint64, optional, tag="15")]
+    pub sat: ::core::option::Option<u64>,
+    /// Rarity of the inscribed sat
+    #[prost(enumeration="Rarity", tag="16")]
+    pub rarity: i32,
+    /// Name, degree and decimal notations of the inscribed sat
+    #[prost(string, optional, tag="17")]
+    pub sat_name: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag="18")]
+    pub sat_degree: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag="19")]
+    pub sat_decimal: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -94,4 +250,585 @@ pub struct Inscriptions {
     #[prost(message, repeated, tag="1")]
     pub inscriptions: ::prost::alloc::vec::Vec<Inscription>,
 }
+/// Inscriptions made on a given sat, in order
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SatInscriptions {
+    #[prost(string, repeated, tag="1")]
+    pub inscriptions: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Whether the first inscription on the sat is cursed or vindicated,
+    /// in which case the next one is not a cursed reinscription
+    #[prost(bool, tag="2")]
+    pub first_cursed_or_vindicated: bool,
+}
+/// Inscription held by a UTXO
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InscriptionLocation {
+    #[prost(string, tag="1")]
+    pub id: ::prost::alloc::string::String,
+    /// Inscribed sat
+    #[prost(uint64, tag="2")]
+    pub sat: u64,
+    /// Offset of the inscribed sat in the UTXO
+    #[prost(uint64, tag="3")]
+    pub offset: u64,
+}
+/// Inscriptions held by a given UTXO
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UtxoInscriptions {
+    #[prost(message, repeated, tag="1")]
+    pub inscriptions: ::prost::alloc::vec::Vec<InscriptionLocation>,
+    #[prost(string, optional, tag="2")]
+    pub address: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Move of an inscribed sat from one UTXO to another
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InscriptionTransfer {
+    #[prost(string, tag="1")]
+    pub inscription_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub from_outpoint: ::prost::alloc::string::String,
+    /// Empty for lost inscriptions
+    #[prost(string, tag="3")]
+    pub to_outpoint: ::prost::alloc::string::String,
+    /// Offset of the inscribed sat in the new UTXO
+    #[prost(uint64, tag="4")]
+    pub offset: u64,
+    #[prost(string, optional, tag="5")]
+    pub from_address: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag="6")]
+    pub to_address: ::core::option::Option<::prost::alloc::string::String>,
+    /// Transaction spending the UTXO holding the inscription
+    #[prost(string, tag="7")]
+    pub txid: ::prost::alloc::string::String,
+    #[prost(uint64, tag="8")]
+    pub block_height: u64,
+    #[prost(enumeration="InscriptionState", tag="9")]
+    pub state: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InscriptionTransfers {
+    #[prost(message, repeated, tag="1")]
+    pub transfers: ::prost::alloc::vec::Vec<InscriptionTransfer>,
+}
+/// Move of a sat of rarity uncommon or better
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RareSatMove {
+    #[prost(uint64, tag="1")]
+    pub sat: u64,
+    #[prost(enumeration="Rarity", tag="2")]
+    pub rarity: i32,
+    /// Empty for sats mined in the block
+    #[prost(string, tag="3")]
+    pub from_outpoint: ::prost::alloc::string::String,
+    /// Empty for lost sats
+    #[prost(string, tag="4")]
+    pub to_outpoint: ::prost::alloc::string::String,
+    /// Offset of the sat in the new UTXO
+    #[prost(uint64, tag="5")]
+    pub offset: u64,
+    #[prost(string, optional, tag="6")]
+    pub from_address: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag="7")]
+    pub to_address: ::core::option::Option<::prost::alloc::string::String>,
+    /// Transaction spending the UTXO holding the sat, or coinbase mining it
+    #[prost(string, tag="8")]
+    pub txid: ::prost::alloc::string::String,
+    #[prost(uint64, tag="9")]
+    pub block_height: u64,
+    /// Halving epoch, difficulty adjustment period and cycle in which the sat was mined
+    #[prost(uint64, tag="10")]
+    pub epoch: u64,
+    #[prost(uint64, tag="11")]
+    pub period: u64,
+    #[prost(uint64, tag="12")]
+    pub cycle: u64,
+    /// Name, degree and decimal notations of the sat
+    #[prost(string, tag="13")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag="14")]
+    pub degree: ::prost::alloc::string::String,
+    #[prost(string, tag="15")]
+    pub decimal: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RareSatMoves {
+    #[prost(message, repeated, tag="1")]
+    pub moves: ::prost::alloc::vec::Vec<RareSatMove>,
+}
+/// Runestone of a transaction, decoded from its first `OP_RETURN OP_13` output
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Runestone {
+    #[prost(string, tag="1")]
+    pub txid: ::prost::alloc::string::String,
+    /// Index of the transaction in its block
+    #[prost(uint32, tag="2")]
+    pub tx_index: u32,
+    #[prost(uint64, tag="3")]
+    pub block_height: u64,
+    #[prost(message, optional, tag="4")]
+    pub etching: ::core::option::Option<Etching>,
+    /// ID (`block:tx`) of the rune minted
+    #[prost(string, optional, tag="5")]
+    pub mint: ::core::option::Option<::prost::alloc::string::String>,
+    /// Output receiving the runes which are not transferred by edicts
+    #[prost(uint32, optional, tag="6")]
+    pub pointer: ::core::option::Option<u32>,
+    #[prost(message, repeated, tag="7")]
+    pub edicts: ::prost::alloc::vec::Vec<Edict>,
+    /// Whether the runestone is malformed. Cenotaphs burn the runes of the
+    /// transaction inputs
+    #[prost(bool, tag="8")]
+    pub cenotaph: bool,
+    /// Reason the runestone is a cenotaph
+    #[prost(enumeration="RunestoneFlaw", tag="9")]
+    pub flaw: i32,
+}
+/// Rune amounts are unsigned 128-bit integers, written in base 10
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Etching {
+    /// ID (`block:tx`) of the etched rune
+    #[prost(string, tag="1")]
+    pub rune_id: ::prost::alloc::string::String,
+    /// Name of the rune. Valid etchings without a name get a reserved name
+    #[prost(string, optional, tag="2")]
+    pub rune: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(uint32, optional, tag="3")]
+    pub divisibility: ::core::option::Option<u32>,
+    #[prost(string, optional, tag="4")]
+    pub premine: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(uint32, optional, tag="5")]
+    pub spacers: ::core::option::Option<u32>,
+    #[prost(string, optional, tag="6")]
+    pub symbol: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(message, optional, tag="7")]
+    pub terms: ::core::option::Option<MintTerms>,
+    #[prost(bool, tag="8")]
+    pub turbo: bool,
+    /// Whether the etching creates the rune: its name is not reserved, long
+    /// enough at the block height and not etched yet, and an input commits to it
+    #[prost(bool, tag="9")]
+    pub valid: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MintTerms {
+    #[prost(string, optional, tag="1")]
+    pub amount: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag="2")]
+    pub cap: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(uint64, optional, tag="3")]
+    pub height_start: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag="4")]
+    pub height_end: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag="5")]
+    pub offset_start: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag="6")]
+    pub offset_end: ::core::option::Option<u64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Edict {
+    /// ID (`block:tx`) of the rune transferred
+    #[prost(string, tag="1")]
+    pub rune_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub amount: ::prost::alloc::string::String,
+    #[prost(uint32, tag="3")]
+    pub output: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Runestones {
+    #[prost(message, repeated, tag="1")]
+    pub runestones: ::prost::alloc::vec::Vec<Runestone>,
+}
+/// Balance of a rune, in base 10
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RuneBalance {
+    /// ID (`block:tx`) of the rune
+    #[prost(string, tag="1")]
+    pub rune_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub amount: ::prost::alloc::string::String,
+}
+/// Runes held by a given UTXO, ordered by rune ID
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UtxoRuneBalances {
+    #[prost(message, repeated, tag="1")]
+    pub balances: ::prost::alloc::vec::Vec<RuneBalance>,
+    #[prost(string, optional, tag="2")]
+    pub address: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Change of the balance of a rune held by a UTXO
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RuneBalanceDelta {
+    #[prost(string, tag="1")]
+    pub rune_id: ::prost::alloc::string::String,
+    /// Empty for runes burned without being assigned to any output
+    #[prost(string, tag="2")]
+    pub outpoint: ::prost::alloc::string::String,
+    #[prost(string, optional, tag="3")]
+    pub address: ::core::option::Option<::prost::alloc::string::String>,
+    /// Amount received, spent or burned, in base 10
+    #[prost(string, tag="4")]
+    pub amount: ::prost::alloc::string::String,
+    #[prost(enumeration="RuneBalanceOperation", tag="5")]
+    pub operation: i32,
+    /// Transaction moving the runes
+    #[prost(string, tag="6")]
+    pub txid: ::prost::alloc::string::String,
+    #[prost(uint64, tag="7")]
+    pub block_height: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RuneBalanceDeltas {
+    #[prost(message, repeated, tag="1")]
+    pub deltas: ::prost::alloc::vec::Vec<RuneBalanceDelta>,
+}
+/// Mint accepted by the terms of its rune
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RuneMint {
+    /// ID (`block:tx`) of the rune
+    #[prost(string, tag="1")]
+    pub rune_id: ::prost::alloc::string::String,
+    /// Index of the minting transaction in its block
+    #[prost(uint32, tag="2")]
+    pub tx_index: u32,
+    /// Amount minted, in base 10
+    #[prost(string, tag="3")]
+    pub amount: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RuneMints {
+    #[prost(message, repeated, tag="1")]
+    pub mints: ::prost::alloc::vec::Vec<RuneMint>,
+}
+/// Transaction from the first rune height on, as needed to replay the runes it moved
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RuneTransaction {
+    #[prost(uint64, tag="1")]
+    pub height: u64,
+    /// Index of the transaction in its block
+    #[prost(uint32, tag="2")]
+    pub tx_index: u32,
+    /// Raw transaction, only kept for transactions carrying a runestone or a cenotaph
+    #[prost(bytes="vec", tag="3")]
+    pub raw: ::prost::alloc::vec::Vec<u8>,
+    /// UTXOs spent by the other transactions
+    #[prost(string, repeated, tag="4")]
+    pub inputs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Output receiving the runes of the inputs of the other transactions
+    #[prost(uint32, tag="5")]
+    pub default_output: u32,
+    /// Rune etched by the transaction, if valid
+    #[prost(string, optional, tag="6")]
+    pub etched_rune: ::core::option::Option<::prost::alloc::string::String>,
+    /// Mint accepted in the transaction
+    #[prost(message, optional, tag="7")]
+    pub mint: ::core::option::Option<RuneMint>,
+}
+/// Supply of a rune after a block. Amounts are written in base 10
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RuneSupply {
+    /// ID (`block:tx`) of the rune
+    #[prost(string, tag="1")]
+    pub rune_id: ::prost::alloc::string::String,
+    #[prost(string, optional, tag="2")]
+    pub rune: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, tag="3")]
+    pub premine: ::prost::alloc::string::String,
+    /// Amount created by each mint
+    #[prost(string, tag="4")]
+    pub mint_amount: ::prost::alloc::string::String,
+    /// Number of mints accepted
+    #[prost(uint64, tag="5")]
+    pub mints: u64,
+    #[prost(string, tag="6")]
+    pub burned: ::prost::alloc::string::String,
+    /// Premine and minted amount, less the amount burned
+    #[prost(string, tag="7")]
+    pub circulating: ::prost::alloc::string::String,
+    /// Block of the last change of the supply
+    #[prost(uint64, tag="8")]
+    pub block_height: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RuneSupplies {
+    #[prost(message, repeated, tag="1")]
+    pub supplies: ::prost::alloc::vec::Vec<RuneSupply>,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum Curse {
+    None = 0,
+    DuplicateField = 1,
+    IncompleteField = 2,
+    NotAtOffsetZero = 3,
+    NotInFirstInput = 4,
+    Pointer = 5,
+    Pushnum = 6,
+    Reinscription = 7,
+    Stutter = 8,
+    UnrecognizedEvenField = 9,
+}
+impl Curse {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Curse::None => "CURSE_NONE",
+            Curse::DuplicateField => "CURSE_DUPLICATE_FIELD",
+            Curse::IncompleteField => "CURSE_INCOMPLETE_FIELD",
+            Curse::NotAtOffsetZero => "CURSE_NOT_AT_OFFSET_ZERO",
+            Curse::NotInFirstInput => "CURSE_NOT_IN_FIRST_INPUT",
+            Curse::Pointer => "CURSE_POINTER",
+            Curse::Pushnum => "CURSE_PUSHNUM",
+            Curse::Reinscription => "CURSE_REINSCRIPTION",
+            Curse::Stutter => "CURSE_STUTTER",
+            Curse::UnrecognizedEvenField => "CURSE_UNRECOGNIZED_EVEN_FIELD",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "CURSE_NONE" => Some(Self::None),
+            "CURSE_DUPLICATE_FIELD" => Some(Self::DuplicateField),
+            "CURSE_INCOMPLETE_FIELD" => Some(Self::IncompleteField),
+            "CURSE_NOT_AT_OFFSET_ZERO" => Some(Self::NotAtOffsetZero),
+            "CURSE_NOT_IN_FIRST_INPUT" => Some(Self::NotInFirstInput),
+            "CURSE_POINTER" => Some(Self::Pointer),
+            "CURSE_PUSHNUM" => Some(Self::Pushnum),
+            "CURSE_REINSCRIPTION" => Some(Self::Reinscription),
+            "CURSE_STUTTER" => Some(Self::Stutter),
+            "CURSE_UNRECOGNIZED_EVEN_FIELD" => Some(Self::UnrecognizedEvenField),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum Rarity {
+    Common = 0,
+    Uncommon = 1,
+    Rare = 2,
+    Epic = 3,
+    Legendary = 4,
+    Mythic = 5,
+}
+impl Rarity {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Rarity::Common => "RARITY_COMMON",
+            Rarity::Uncommon => "RARITY_UNCOMMON",
+            Rarity::Rare => "RARITY_RARE",
+            Rarity::Epic => "RARITY_EPIC",
+            Rarity::Legendary => "RARITY_LEGENDARY",
+            Rarity::Mythic => "RARITY_MYTHIC",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "RARITY_COMMON" => Some(Self::Common),
+            "RARITY_UNCOMMON" => Some(Self::Uncommon),
+            "RARITY_RARE" => Some(Self::Rare),
+            "RARITY_EPIC" => Some(Self::Epic),
+            "RARITY_LEGENDARY" => Some(Self::Legendary),
+            "RARITY_MYTHIC" => Some(Self::Mythic),
+            _ => None,
+        }
+    }
+}
+/// Where an inscribed sat ends up. Burned, unspendable and lost inscriptions
+/// are terminal: they cannot be transferred anymore
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum InscriptionState {
+    /// Held by a spendable output
+    Transferred = 0,
+    /// Held by an OP_RETURN output
+    Burned = 1,
+    /// Paid as fee and held by a coinbase output
+    PaidAsFee = 2,
+    /// Held by a provably unspendable output other than OP_RETURN
+    Unspendable = 3,
+    /// Paid as fee and not claimed by the coinbase
+    Lost = 4,
+}
+impl InscriptionState {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            InscriptionState::Transferred => "INSCRIPTION_STATE_TRANSFERRED",
+            InscriptionState::Burned => "INSCRIPTION_STATE_BURNED",
+            InscriptionState::PaidAsFee => "INSCRIPTION_STATE_PAID_AS_FEE",
+            InscriptionState::Unspendable => "INSCRIPTION_STATE_UNSPENDABLE",
+            InscriptionState::Lost => "INSCRIPTION_STATE_LOST",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "INSCRIPTION_STATE_TRANSFERRED" => Some(Self::Transferred),
+            "INSCRIPTION_STATE_BURNED" => Some(Self::Burned),
+            "INSCRIPTION_STATE_PAID_AS_FEE" => Some(Self::PaidAsFee),
+            "INSCRIPTION_STATE_UNSPENDABLE" => Some(Self::Unspendable),
+            "INSCRIPTION_STATE_LOST" => Some(Self::Lost),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum DiagnosticKind {
+    /// Transaction hex which is not valid hexadecimal, the transaction is skipped
+    InvalidHex = 0,
+    /// Raw transaction which cannot be deserialized, the transaction is skipped
+    InvalidTransaction = 1,
+    /// Block without any transaction
+    EmptyBlock = 2,
+    /// UTXO spent without known sat ranges, it is assumed to hold none
+    MissingSatRanges = 3,
+    /// UTXO spent without known value, it is assumed to hold none
+    MissingValue = 4,
+}
+impl DiagnosticKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            DiagnosticKind::InvalidHex => "DIAGNOSTIC_KIND_INVALID_HEX",
+            DiagnosticKind::InvalidTransaction => "DIAGNOSTIC_KIND_INVALID_TRANSACTION",
+            DiagnosticKind::EmptyBlock => "DIAGNOSTIC_KIND_EMPTY_BLOCK",
+            DiagnosticKind::MissingSatRanges => "DIAGNOSTIC_KIND_MISSING_SAT_RANGES",
+            DiagnosticKind::MissingValue => "DIAGNOSTIC_KIND_MISSING_VALUE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "DIAGNOSTIC_KIND_INVALID_HEX" => Some(Self::InvalidHex),
+            "DIAGNOSTIC_KIND_INVALID_TRANSACTION" => Some(Self::InvalidTransaction),
+            "DIAGNOSTIC_KIND_EMPTY_BLOCK" => Some(Self::EmptyBlock),
+            "DIAGNOSTIC_KIND_MISSING_SAT_RANGES" => Some(Self::MissingSatRanges),
+            "DIAGNOSTIC_KIND_MISSING_VALUE" => Some(Self::MissingValue),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum RunestoneFlaw {
+    None = 0,
+    EdictOutput = 1,
+    EdictRuneId = 2,
+    InvalidScript = 3,
+    Opcode = 4,
+    SupplyOverflow = 5,
+    TrailingIntegers = 6,
+    TruncatedField = 7,
+    UnrecognizedEvenTag = 8,
+    UnrecognizedFlag = 9,
+    Varint = 10,
+}
+impl RunestoneFlaw {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            RunestoneFlaw::None => "RUNESTONE_FLAW_NONE",
+            RunestoneFlaw::EdictOutput => "RUNESTONE_FLAW_EDICT_OUTPUT",
+            RunestoneFlaw::EdictRuneId => "RUNESTONE_FLAW_EDICT_RUNE_ID",
+            RunestoneFlaw::InvalidScript => "RUNESTONE_FLAW_INVALID_SCRIPT",
+            RunestoneFlaw::Opcode => "RUNESTONE_FLAW_OPCODE",
+            RunestoneFlaw::SupplyOverflow => "RUNESTONE_FLAW_SUPPLY_OVERFLOW",
+            RunestoneFlaw::TrailingIntegers => "RUNESTONE_FLAW_TRAILING_INTEGERS",
+            RunestoneFlaw::TruncatedField => "RUNESTONE_FLAW_TRUNCATED_FIELD",
+            RunestoneFlaw::UnrecognizedEvenTag => "RUNESTONE_FLAW_UNRECOGNIZED_EVEN_TAG",
+            RunestoneFlaw::UnrecognizedFlag => "RUNESTONE_FLAW_UNRECOGNIZED_FLAG",
+            RunestoneFlaw::Varint => "RUNESTONE_FLAW_VARINT",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "RUNESTONE_FLAW_NONE" => Some(Self::None),
+            "RUNESTONE_FLAW_EDICT_OUTPUT" => Some(Self::EdictOutput),
+            "RUNESTONE_FLAW_EDICT_RUNE_ID" => Some(Self::EdictRuneId),
+            "RUNESTONE_FLAW_INVALID_SCRIPT" => Some(Self::InvalidScript),
+            "RUNESTONE_FLAW_OPCODE" => Some(Self::Opcode),
+            "RUNESTONE_FLAW_SUPPLY_OVERFLOW" => Some(Self::SupplyOverflow),
+            "RUNESTONE_FLAW_TRAILING_INTEGERS" => Some(Self::TrailingIntegers),
+            "RUNESTONE_FLAW_TRUNCATED_FIELD" => Some(Self::TruncatedField),
+            "RUNESTONE_FLAW_UNRECOGNIZED_EVEN_TAG" => Some(Self::UnrecognizedEvenTag),
+            "RUNESTONE_FLAW_UNRECOGNIZED_FLAG" => Some(Self::UnrecognizedFlag),
+            "RUNESTONE_FLAW_VARINT" => Some(Self::Varint),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum RuneBalanceOperation {
+    /// Received by a spendable output
+    Received = 0,
+    /// Spent by an input
+    Spent = 1,
+    /// Assigned to an OP_RETURN output, left without any output to go to,
+    /// or spent by a cenotaph
+    Burned = 2,
+}
+impl RuneBalanceOperation {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            RuneBalanceOperation::Received => "RUNE_BALANCE_OPERATION_RECEIVED",
+            RuneBalanceOperation::Spent => "RUNE_BALANCE_OPERATION_SPENT",
+            RuneBalanceOperation::Burned => "RUNE_BALANCE_OPERATION_BURNED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "RUNE_BALANCE_OPERATION_RECEIVED" => Some(Self::Received),
+            "RUNE_BALANCE_OPERATION_SPENT" => Some(Self::Spent),
+            "RUNE_BALANCE_OPERATION_BURNED" => Some(Self::Burned),
+            _ => None,
+        }
+    }
+}
 // @@protoc_insertion_point(module)
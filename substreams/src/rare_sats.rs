@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+
+use crate::address::address_from_script;
+use crate::decoded::DecodedBlock;
+use crate::pb::ordinals::v1::RareSatMove;
+use crate::sat_ranges::BlockSatRanges;
+use crate::sats_utils::{block_starting_sat, subsidy, Sat};
+use crate::transfers::output_holding;
+
+/// Sats of rarity uncommon or better moved by the transactions of a block
+#[derive(Debug, Default, PartialEq)]
+pub struct BlockRareSats {
+    /// Moves of the rare sats held by the UTXOs spent in the block, in block order,
+    /// preceded by the rare sat mined by the block
+    pub moves: Vec<RareSatMove>,
+    /// UTXOs holding rare sats created and left unspent by the block, and their address
+    pub created: BTreeMap<String, Option<String>>,
+    /// UTXOs holding rare sats created in previous blocks and spent in the block
+    pub spent: Vec<String>,
+}
+
+impl BlockRareSats {
+    /// Moves `sat`, held by `from`, to the output of the `tx_idx`th transaction of
+    /// `block` holding it, or to the coinbase output holding it if it is paid as fee
+    fn move_sat(
+        &mut self,
+        block: &DecodedBlock,
+        block_sat_ranges: &BlockSatRanges,
+        tx_idx: usize,
+        sat: u64,
+        from: (String, Option<String>),
+    ) {
+        let tx = &block.txs[tx_idx];
+        let destination = output_holding(tx, &block_sat_ranges.txs[tx_idx].outputs, sat)
+            .or_else(|| output_holding(&block.txs[0], &block_sat_ranges.txs[0].outputs, sat));
+
+        let (to_utxo, to_address, offset) = match destination {
+            Some((utxo, output, offset)) => (utxo, address_from_script(&output.script_pubkey, block.chain), offset),
+            // Sats paid as fee and not claimed by the coinbase are lost
+            None => (String::new(), None, 0),
+        };
+
+        let (from_utxo, from_address) = from;
+        self.moves.push(RareSatMove {
+            sat,
+            rarity: Sat(sat).rarity() as i32,
+            epoch: Sat(sat).epoch(),
+            period: Sat(sat).period(),
+            cycle: Sat(sat).cycle(),
+            name: Sat(sat).name(),
+            degree: Sat(sat).degree(),
+            decimal: Sat(sat).decimal(),
+            from_outpoint: from_utxo,
+            to_outpoint: to_utxo.clone(),
+            offset,
+            from_address,
+            to_address: to_address.clone(),
+            txid: tx.txid.clone(),
+            block_height: block.height,
+        });
+
+        if !to_utxo.is_empty() {
+            self.created.insert(to_utxo, to_address);
+        }
+    }
+}
+
+/// Follows the sats of rarity uncommon or better through the transactions of `block`.
+/// The first sat of the block subsidy is mined by the coinbase, without any previous
+/// UTXO. Rare sats paid as fee move to the coinbase output receiving them, if any,
+/// and are lost otherwise.
+///
+/// `lookup` returns the address of the UTXOs holding rare sats created in previous
+/// blocks. UTXOs created and spent within the block are resolved locally.
+pub fn follow_rare_sats<F>(block: &DecodedBlock, block_sat_ranges: &BlockSatRanges, mut lookup: F) -> BlockRareSats
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let mut result = BlockRareSats::default();
+
+    // Empty blocks do not even mine their first sat
+    if subsidy(block.height) > 0 && !block.txs.is_empty() {
+        result.move_sat(block, block_sat_ranges, 0, block_starting_sat(block.height), (String::new(), None));
+    }
+
+    for (tx_idx, tx_sat_ranges) in block_sat_ranges.txs.iter().enumerate().skip(1) {
+        for (utxo, ranges) in &tx_sat_ranges.inputs {
+            let rare_sats = ranges.rare_sats();
+            if rare_sats.is_empty() {
+                continue;
+            }
+
+            let from_address = match result.created.remove(utxo) {
+                Some(address) => address,
+                None => {
+                    result.spent.push(utxo.clone());
+                    lookup(utxo)
+                }
+            };
+
+            for sat in rare_sats {
+                result.move_sat(block, block_sat_ranges, tx_idx, sat, (utxo.clone(), from_address.clone()));
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::ScriptBuf;
+
+    use super::*;
+    use crate::decoded::{test_utils::{bare_tx, block}, DecodedTransaction};
+    use crate::pb::ordinals::v1::Rarity;
+    use crate::sat_ranges::test_utils::{ranges, utxos};
+    use crate::sat_ranges::TransactionSatRanges;
+
+    const SCRIPT: &str = "76a914534e48e9a49ce7ebf8d84c8313e4edfa48852fa188ac";
+    const ADDRESS: &str = "18bUsFHLgFotUqAL9ftLBVenJDVP7M64Nu";
+
+    /// `bare_tx` whose outputs pay to `ADDRESS`
+    fn tx(txid: &str, outputs: usize) -> DecodedTransaction {
+        let mut tx = bare_tx(txid, outputs);
+        for output in &mut tx.raw.output {
+            output.script_pubkey = ScriptBuf::from_bytes(hex::decode(SCRIPT).unwrap());
+        }
+        tx
+    }
+
+    #[test]
+    fn test_follow_rare_sats() {
+        let block = block(1, vec![tx("cb", 1), tx("a", 2), tx("b", 0)]);
+        let block_sat_ranges = BlockSatRanges {
+            txs: vec![
+                TransactionSatRanges {
+                    outputs: utxos(&[("cb:0", &[(5_000_000_000, 5_000_000_000), (0, 4)])]),
+                    ..Default::default()
+                },
+                TransactionSatRanges {
+                    inputs: utxos(&[("prev:0", &[(0, 10)]), ("common:0", &[(20, 5)])]),
+                    outputs: utxos(&[("a:0", &[(0, 4)]), ("a:1", &[(4, 6), (20, 5)])]),
+                    ..Default::default()
+                },
+                // Pays the mythic sat as fee
+                TransactionSatRanges {
+                    inputs: utxos(&[("a:0", &[(0, 4)])]),
+                    fee: ranges(&[(0, 4)]),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let result = follow_rare_sats(&block, &block_sat_ranges, |utxo| match utxo {
+            "prev:0" => Some("bc1prev".into()),
+            _ => None,
+        });
+
+        assert_eq!(
+            result.moves.iter()
+                .map(|mv| (
+                    mv.sat,
+                    mv.rarity,
+                    mv.from_outpoint.as_str(),
+                    mv.from_address.as_deref(),
+                    mv.to_outpoint.as_str(),
+                    mv.offset,
+                    mv.txid.as_str(),
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (5_000_000_000, Rarity::Uncommon as i32, "", None, "cb:0", 0, "cb"),
+                (0, Rarity::Mythic as i32, "prev:0", Some("bc1prev"), "a:0", 0, "a"),
+                (0, Rarity::Mythic as i32, "a:0", Some(ADDRESS), "cb:0", 5_000_000_000, "b"),
+            ],
+        );
+        assert!(result.moves.iter().all(|mv| mv.to_address.as_deref() == Some(ADDRESS)));
+
+        // Inputs without rare sats are not looked up
+        assert_eq!(result.spent, vec!["prev:0".to_string()]);
+        assert_eq!(result.created.keys().collect::<Vec<_>>(), vec!["cb:0"]);
+    }
+
+    #[test]
+    fn test_lost_subsidy() {
+        // The coinbase of the first block of the second cycle does not claim any sat
+        let block = block(1_260_000, vec![tx("cb", 0)]);
+        let block_sat_ranges = BlockSatRanges { txs: vec![TransactionSatRanges::default()], ..Default::default() };
+
+        let result = follow_rare_sats(&block, &block_sat_ranges, |_| None);
+
+        assert_eq!(result.moves.len(), 1);
+        assert_eq!(result.moves[0].rarity, Rarity::Legendary as i32);
+        assert_eq!((result.moves[0].epoch, result.moves[0].period, result.moves[0].cycle), (6, 625, 1));
+        assert_eq!(result.moves[0].degree, "1°0′0″0‴");
+        assert_eq!(result.moves[0].decimal, "1260000.0");
+        assert_eq!(result.moves[0].name.parse::<Sat>().unwrap(), Sat(result.moves[0].sat));
+        assert_eq!(result.moves[0].to_outpoint, "");
+        assert_eq!(result.moves[0].to_address, None);
+        assert!(result.created.is_empty());
+    }
+}
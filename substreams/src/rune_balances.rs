@@ -0,0 +1,602 @@
+use std::collections::{BTreeMap, HashMap};
+
+use bitcoin::consensus::{deserialize, serialize};
+
+use crate::decoded::{DecodedBlock, DecodedTransaction};
+use crate::error::Diagnostics;
+use crate::mints::Mint;
+use crate::ord::chain::Chain;
+use crate::pb::ordinals::v1::{
+    RuneBalance, RuneBalanceDelta, RuneBalanceOperation, RuneMint, RuneTransaction, UtxoRuneBalances,
+};
+use crate::runes::edict::Edict;
+use crate::runes::rune::Rune;
+use crate::runes::rune_id::RuneId;
+use crate::runes::runestone::{Artifact, Runestone};
+
+/// Amounts of runes, by rune ID
+type Balances = BTreeMap<RuneId, u128>;
+
+/// Rune balances changed by the transactions of a block
+#[derive(Debug, Default, PartialEq)]
+pub struct BlockRuneBalances {
+    /// Changes of the balances of the UTXOs spent and created in the block, and
+    /// runes burned, in block order
+    pub deltas: Vec<RuneBalanceDelta>,
+    /// Balances of the UTXOs created and left unspent by the block
+    pub created: BTreeMap<String, UtxoRuneBalances>,
+    /// UTXOs holding runes created in previous blocks and spent in the block
+    pub spent: Vec<String>,
+}
+
+impl BlockRuneBalances {
+    fn push(
+        &mut self,
+        block: &DecodedBlock,
+        tx: &DecodedTransaction,
+        operation: RuneBalanceOperation,
+        (outpoint, address): (String, Option<String>),
+        balances: &Balances,
+    ) {
+        for (id, amount) in balances {
+            if *amount == 0 {
+                continue;
+            }
+
+            self.deltas.push(RuneBalanceDelta {
+                rune_id: id.to_string(),
+                outpoint: outpoint.clone(),
+                address: address.clone(),
+                amount: amount.to_string(),
+                operation: operation as i32,
+                txid: tx.txid.clone(),
+                block_height: block.height,
+            });
+        }
+    }
+}
+
+/// Moves the runes held by the inputs of each transaction of `block` to its outputs
+/// following its runestone. The runes `minted` by the transaction and the premine of
+/// the `etched` runes are allocated along with the runes of the inputs, and the runes
+/// left unallocated by the edicts go to the output of its pointer or to the first
+/// non-OP_RETURN output. Runes assigned to OP_RETURN outputs, left without any
+/// non-OP_RETURN output or spent by a cenotaph are burned.
+///
+/// `lookup` returns the balances of the UTXOs created in previous blocks. UTXOs
+/// created and spent within the block are resolved locally.
+pub fn apply_runestones<F>(
+    block: &DecodedBlock,
+    etched: &BTreeMap<RuneId, Rune>,
+    minted: &BTreeMap<u32, Mint>,
+    mut lookup: F,
+) -> BlockRuneBalances
+where
+    F: FnMut(&str) -> Option<UtxoRuneBalances>,
+{
+    let mut result = BlockRuneBalances::default();
+
+    if block.height < block.chain.first_rune_height() {
+        return result;
+    }
+
+    for tx in &block.txs {
+        let artifact = Runestone::decipher(&tx.raw);
+
+        // Runes of the inputs, to be allocated to the outputs
+        let mut unallocated = Balances::new();
+        for utxo in tx.inputs() {
+            let utxo_balances = match result.created.remove(&utxo) {
+                Some(utxo_balances) => utxo_balances,
+                None => match lookup(&utxo) {
+                    Some(utxo_balances) => {
+                        result.spent.push(utxo.clone());
+                        utxo_balances
+                    }
+                    None => continue,
+                },
+            };
+
+            let balances = parse_balances(&utxo_balances);
+            result.push(block, tx, RuneBalanceOperation::Spent, (utxo, utxo_balances.address), &balances);
+            add(&mut unallocated, balances);
+        }
+
+        if let Some(mint) = minted.get(&tx.idx) {
+            *unallocated.entry(mint.id).or_default() += mint.amount;
+        }
+
+        let mut allocated = vec![Balances::new(); tx.raw.output.len()];
+
+        if let Some(Artifact::Runestone(runestone)) = &artifact {
+            let id = RuneId { block: block.height, tx: tx.idx };
+            let etched = match runestone.etching {
+                Some(etching) if etched.contains_key(&id) => {
+                    *unallocated.entry(id).or_default() += etching.premine.unwrap_or_default();
+                    Some(id)
+                }
+                _ => None,
+            };
+            apply_edicts(tx, runestone, etched, &mut unallocated, &mut allocated);
+        }
+
+        let default_output = match &artifact {
+            Some(Artifact::Cenotaph(_)) => None,
+            Some(Artifact::Runestone(Runestone { pointer: Some(pointer), .. })) => Some(*pointer as usize),
+            _ => tx.raw.output.iter().position(|output| !output.script_pubkey.is_op_return()),
+        };
+
+        match default_output {
+            Some(vout) => add(&mut allocated[vout], unallocated),
+            None => result.push(block, tx, RuneBalanceOperation::Burned, (String::new(), None), &unallocated),
+        }
+
+        for (vout, balances) in allocated.into_iter().enumerate() {
+            if balances.values().all(|amount| *amount == 0) {
+                continue;
+            }
+
+            let utxo = tx.utxo(vout);
+            if tx.raw.output[vout].script_pubkey.is_op_return() {
+                result.push(block, tx, RuneBalanceOperation::Burned, (utxo, None), &balances);
+                continue;
+            }
+
+            let address = tx.output_address(vout, block.chain);
+            result.push(block, tx, RuneBalanceOperation::Received, (utxo.clone(), address.clone()), &balances);
+            result.created.insert(utxo, UtxoRuneBalances {
+                balances: balances_proto(&balances),
+                address,
+            });
+        }
+    }
+
+    result
+}
+
+/// Records of the transactions of `block`, by txid, from which the runes they moved
+/// can be replayed later on. Transactions carrying a runestone or a cenotaph are kept
+/// whole, along with the rune they etched and the mint they were accepted, while the
+/// others only keep their inputs and the output receiving the runes of their inputs.
+pub fn rune_transactions(
+    block: &DecodedBlock,
+    etched: &BTreeMap<RuneId, Rune>,
+    minted: &BTreeMap<u32, Mint>,
+) -> Vec<(String, RuneTransaction)> {
+    if block.height < block.chain.first_rune_height() {
+        return vec![];
+    }
+
+    block.txs.iter()
+        .filter_map(|tx| {
+            let mut record = RuneTransaction { height: block.height, tx_index: tx.idx, ..Default::default() };
+
+            if Runestone::decipher(&tx.raw).is_some() {
+                record.raw = serialize(&tx.raw);
+                record.etched_rune = etched.get(&RuneId { block: block.height, tx: tx.idx }).map(Rune::to_string);
+                record.mint = minted.get(&tx.idx).map(|mint| RuneMint {
+                    rune_id: mint.id.to_string(),
+                    tx_index: tx.idx,
+                    amount: mint.amount.to_string(),
+                });
+            } else {
+                // Transactions without any non-OP_RETURN output burn the runes of their inputs
+                let default_output = tx.raw.output.iter().position(|output| !output.script_pubkey.is_op_return())?;
+                record.default_output = default_output as u32;
+                record.inputs = tx.inputs().collect();
+            }
+
+            Some((tx.txid.clone(), record))
+        })
+        .collect()
+}
+
+/// Rune balances of the UTXOs created in previous blocks, replayed from the records
+/// of `rune_transactions` back to the first rune height
+pub struct RuneHistory<F> {
+    /// Network of the recorded transactions
+    chain: Chain,
+    /// Returns the record of a transaction by txid
+    transactions: F,
+    /// Records read so far by txid
+    records: HashMap<String, Option<RuneTransaction>>,
+    /// Balances of the UTXOs resolved so far, `None` for UTXOs without any rune
+    resolved: HashMap<String, Option<UtxoRuneBalances>>,
+}
+
+impl<F> RuneHistory<F>
+where
+    F: FnMut(&str) -> Option<RuneTransaction>,
+{
+    pub fn new(chain: Chain, transactions: F) -> Self {
+        Self { chain, transactions, records: HashMap::new(), resolved: HashMap::new() }
+    }
+
+    /// Runes held by `utxo`, `None` if it does not hold any. UTXOs created by
+    /// transactions without a record do not hold any rune.
+    pub fn balances(&mut self, utxo: &str) -> Option<UtxoRuneBalances> {
+        // UTXOs left to resolve, each one resolved once the UTXOs above it are
+        let mut pending = vec![utxo.to_string()];
+
+        while let Some(next) = pending.last().cloned() {
+            if self.resolved.contains_key(&next) {
+                pending.pop();
+                continue;
+            }
+
+            let record = next.rsplit_once(':')
+                .and_then(|(txid, vout)| Some((txid, vout.parse::<u32>().ok()?)))
+                .and_then(|(txid, vout)| Some((txid.to_string(), vout, self.record(txid)?)));
+            let (txid, vout, record) = match record {
+                Some(record) => record,
+                None => {
+                    self.resolved.insert(next, None);
+                    continue;
+                }
+            };
+
+            if record.raw.is_empty() {
+                // Only the default output receives the runes of the inputs
+                if vout != record.default_output {
+                    self.resolved.insert(next, None);
+                    continue;
+                }
+
+                let missing = self.missing(record.inputs.iter().cloned());
+                if !missing.is_empty() {
+                    pending.extend(missing);
+                    continue;
+                }
+
+                let mut balances = Balances::new();
+                for input in &record.inputs {
+                    if let Some(Some(utxo_balances)) = self.resolved.get(input) {
+                        add(&mut balances, parse_balances(utxo_balances));
+                    }
+                }
+                let utxo_balances = balances.values().any(|amount| *amount > 0).then(|| UtxoRuneBalances {
+                    balances: balances_proto(&balances),
+                    address: None,
+                });
+                self.resolved.insert(next, utxo_balances);
+            } else {
+                let tx = match deserialize(&record.raw) {
+                    Ok(raw) => DecodedTransaction { txid, idx: record.tx_index, vsize: 0, raw },
+                    Err(_) => {
+                        self.resolved.insert(next, None);
+                        continue;
+                    }
+                };
+
+                let missing = self.missing(tx.inputs());
+                if !missing.is_empty() {
+                    pending.extend(missing);
+                    continue;
+                }
+
+                let etched = record.etched_rune.iter()
+                    .filter_map(|rune| rune.parse().ok())
+                    .map(|rune| (RuneId { block: record.height, tx: record.tx_index }, rune))
+                    .collect();
+                let minted = record.mint.iter()
+                    .filter_map(|mint| {
+                        let id = mint.rune_id.parse().ok()?;
+                        Some((mint.tx_index, Mint { id, amount: mint.amount.parse().ok()? }))
+                    })
+                    .collect();
+
+                let outputs = tx.raw.output.len();
+                let block = DecodedBlock {
+                    chain: self.chain,
+                    height: record.height,
+                    time: 0,
+                    txs: vec![tx],
+                    diagnostics: Diagnostics::default(),
+                };
+                let resolved = &self.resolved;
+                let mut replayed = apply_runestones(&block, &etched, &minted, |input| resolved.get(input).cloned().flatten());
+
+                for vout in 0..outputs {
+                    let utxo = block.txs[0].utxo(vout);
+                    let utxo_balances = replayed.created.remove(&utxo);
+                    self.resolved.insert(utxo, utxo_balances);
+                }
+                self.resolved.entry(next).or_insert(None);
+            }
+        }
+
+        self.resolved[utxo].clone()
+    }
+
+    fn record(&mut self, txid: &str) -> Option<RuneTransaction> {
+        let transactions = &mut self.transactions;
+        self.records.entry(txid.to_string()).or_insert_with(|| transactions(txid)).clone()
+    }
+
+    /// UTXOs among `utxos` which are not resolved yet
+    fn missing(&self, utxos: impl Iterator<Item = String>) -> Vec<String> {
+        utxos.filter(|utxo| !self.resolved.contains_key(utxo)).collect()
+    }
+}
+
+/// Allocates the runes of `unallocated` to the outputs of `tx` following the edicts
+/// of its runestone. Edicts of runes which are not held, and the part of their amount
+/// exceeding the unallocated balance, are ignored.
+fn apply_edicts(
+    tx: &DecodedTransaction,
+    runestone: &Runestone,
+    etched: Option<RuneId>,
+    unallocated: &mut Balances,
+    allocated: &mut [Balances],
+) {
+    let outputs = &tx.raw.output;
+
+    for &Edict { id, amount, output } in &runestone.edicts {
+        // Rune ID 0:0 stands for the rune etched by the transaction
+        let id = if id == RuneId::default() {
+            match etched {
+                Some(id) => id,
+                None => continue,
+            }
+        } else {
+            id
+        };
+
+        let balance = match unallocated.get_mut(&id) {
+            Some(balance) => balance,
+            None => continue,
+        };
+
+        let mut allocate = |balance: &mut u128, amount: u128, vout: usize| {
+            if amount > 0 {
+                *balance -= amount;
+                *allocated[vout].entry(id).or_default() += amount;
+            }
+        };
+
+        let output = output as usize;
+        if output == outputs.len() {
+            // Split between all the non-OP_RETURN outputs
+            let destinations = outputs.iter()
+                .enumerate()
+                .filter(|(_, output)| !output.script_pubkey.is_op_return())
+                .map(|(vout, _)| vout)
+                .collect::<Vec<_>>();
+
+            if destinations.is_empty() {
+                continue;
+            }
+
+            if amount == 0 {
+                // The whole balance is split evenly, the first outputs get the remainder
+                let share = *balance / destinations.len() as u128;
+                let remainder = (*balance % destinations.len() as u128) as usize;
+                for (i, vout) in destinations.into_iter().enumerate() {
+                    allocate(balance, if i < remainder { share + 1 } else { share }, vout);
+                }
+            } else {
+                for vout in destinations {
+                    allocate(balance, amount.min(*balance), vout);
+                }
+            }
+        } else {
+            // Zero stands for the whole balance
+            let amount = if amount == 0 { *balance } else { amount.min(*balance) };
+            allocate(balance, amount, output);
+        }
+    }
+}
+
+fn add(balances: &mut Balances, other: Balances) {
+    for (id, amount) in other {
+        *balances.entry(id).or_default() += amount;
+    }
+}
+
+fn parse_balances(utxo_balances: &UtxoRuneBalances) -> Balances {
+    utxo_balances.balances.iter()
+        .filter_map(|balance| Some((balance.rune_id.parse().ok()?, balance.amount.parse().ok()?)))
+        .collect()
+}
+
+fn balances_proto(balances: &Balances) -> Vec<RuneBalance> {
+    balances.iter()
+        .filter(|(_, amount)| **amount > 0)
+        .map(|(id, amount)| RuneBalance { rune_id: id.to_string(), amount: amount.to_string() })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoded::test_utils::{block, tx, utxo};
+    use crate::runes::test_utils::runestone_tx;
+
+    const HEIGHT: u64 = 840_000;
+    const RUNE: RuneId = RuneId { block: 800_000, tx: 1 };
+    const OTHER: RuneId = RuneId { block: 800_000, tx: 2 };
+
+    fn held(balances: &[(RuneId, u128)]) -> UtxoRuneBalances {
+        UtxoRuneBalances {
+            balances: balances_proto(&balances.iter().copied().collect()),
+            address: None,
+        }
+    }
+
+    fn deltas(result: &BlockRuneBalances) -> Vec<(RuneBalanceOperation, String, String, u128)> {
+        result.deltas.iter()
+            .map(|delta| (
+                RuneBalanceOperation::from_i32(delta.operation).unwrap(),
+                delta.outpoint.clone(),
+                delta.rune_id.clone(),
+                delta.amount.parse().unwrap(),
+            ))
+            .collect()
+    }
+
+    #[test]
+    fn test_etching_and_edicts() {
+        use RuneBalanceOperation::*;
+
+        let id = RuneId { block: HEIGHT, tx: 1 }.to_string();
+        let block = block(HEIGHT, vec![
+            tx("cb", &[], &[]),
+            // Etches 1000 runes: 100 to output 1 then the rest split between outputs 1 to 3
+            runestone_tx("a", &[], &[2, 1, 4, 4, 6, 1_000, 0, 0, 0, 100, 1, 0, 0, 0, 4], 3),
+            // Sends 50 of them to output 2 and the rest to the pointer, output 1
+            runestone_tx("b", &[("a", 1)], &[22, 1, 0, HEIGHT as u128, 1, 50, 2], 2),
+        ]);
+
+        // Etchings which are not valid do not premine anything
+        assert!(apply_runestones(&block, &BTreeMap::new(), &BTreeMap::new(), |_| None).deltas.is_empty());
+
+        let etched = BTreeMap::from([(RuneId { block: HEIGHT, tx: 1 }, Rune(4))]);
+        let result = apply_runestones(&block, &etched, &BTreeMap::new(), |_| None);
+        assert_eq!(deltas(&result), vec![
+            (Received, utxo("a", 1), id.clone(), 400),
+            (Received, utxo("a", 2), id.clone(), 300),
+            (Received, utxo("a", 3), id.clone(), 300),
+            (Spent, utxo("a", 1), id.clone(), 400),
+            (Received, utxo("b", 1), id.clone(), 350),
+            (Received, utxo("b", 2), id.clone(), 50),
+        ]);
+        assert!(result.deltas.iter().all(|delta| delta.block_height == HEIGHT));
+        assert_eq!(result.deltas[3].txid, block.txs[2].txid);
+
+        // UTXOs created and spent in the block are neither stored nor deleted
+        assert_eq!(
+            result.created.keys().cloned().collect::<Vec<_>>(),
+            {
+                let mut utxos = vec![utxo("a", 2), utxo("a", 3), utxo("b", 1), utxo("b", 2)];
+                utxos.sort();
+                utxos
+            },
+        );
+        assert!(result.spent.is_empty());
+    }
+
+    #[test]
+    fn test_previous_blocks() {
+        use RuneBalanceOperation::*;
+
+        let block = block(HEIGHT, vec![
+            tx("cb", &[], &[]),
+            // Without a runestone, the runes go to the first output
+            tx("a", &[("prev", 0), ("unknown", 0)], &[1_000, 1_000]),
+        ]);
+
+        let mut lookups = Vec::new();
+        let result = apply_runestones(&block, &BTreeMap::new(), &BTreeMap::new(), |utxo| {
+            lookups.push(utxo.to_string());
+            (utxo == self::utxo("prev", 0)).then(|| held(&[(RUNE, 100), (OTHER, 5)]))
+        });
+
+        assert_eq!(lookups, vec![utxo("prev", 0), utxo("unknown", 0)]);
+        assert_eq!(deltas(&result), vec![
+            (Spent, utxo("prev", 0), RUNE.to_string(), 100),
+            (Spent, utxo("prev", 0), OTHER.to_string(), 5),
+            (Received, utxo("a", 0), RUNE.to_string(), 100),
+            (Received, utxo("a", 0), OTHER.to_string(), 5),
+        ]);
+        assert_eq!(result.spent, vec![utxo("prev", 0)]);
+        assert_eq!(result.created, BTreeMap::from([(utxo("a", 0), held(&[(RUNE, 100), (OTHER, 5)]))]));
+    }
+
+    #[test]
+    fn test_burns() {
+        use RuneBalanceOperation::*;
+
+        let block = block(HEIGHT, vec![
+            tx("cb", &[], &[]),
+            // 30 runes sent to the OP_RETURN output are burned, the rest goes to output 1
+            runestone_tx("a", &[("prev a", 0)], &[0, RUNE.block.into(), RUNE.tx.into(), 30, 0], 1),
+            // Cenotaphs burn all the runes of their inputs
+            runestone_tx("b", &[("prev b", 0)], &[24, 1], 1),
+            // Runes left without any non-OP_RETURN output are burned
+            runestone_tx("c", &[("prev c", 0)], &[], 0),
+        ]);
+
+        let result = apply_runestones(&block, &BTreeMap::new(), &BTreeMap::new(), |_| Some(held(&[(RUNE, 100)])));
+        assert_eq!(deltas(&result), vec![
+            (Spent, utxo("prev a", 0), RUNE.to_string(), 100),
+            (Burned, utxo("a", 0), RUNE.to_string(), 30),
+            (Received, utxo("a", 1), RUNE.to_string(), 70),
+            (Spent, utxo("prev b", 0), RUNE.to_string(), 100),
+            (Burned, String::new(), RUNE.to_string(), 100),
+            (Spent, utxo("prev c", 0), RUNE.to_string(), 100),
+            (Burned, String::new(), RUNE.to_string(), 100),
+        ]);
+        assert_eq!(result.created.keys().collect::<Vec<_>>(), vec![&utxo("a", 1)]);
+    }
+
+    #[test]
+    fn test_mints() {
+        use RuneBalanceOperation::*;
+
+        let block = block(HEIGHT, vec![
+            tx("cb", &[], &[]),
+            runestone_tx("a", &[], &[20, RUNE.block.into(), 20, RUNE.tx.into()], 1),
+            // Cenotaphs burn the runes they mint
+            runestone_tx("b", &[], &[20, RUNE.block.into(), 20, RUNE.tx.into(), 24, 1], 1),
+        ]);
+        let minted = BTreeMap::from([
+            (1, Mint { id: RUNE, amount: 10 }),
+            (2, Mint { id: RUNE, amount: 10 }),
+        ]);
+
+        let result = apply_runestones(&block, &BTreeMap::new(), &minted, |_| None);
+        assert_eq!(deltas(&result), vec![
+            (Received, utxo("a", 1), RUNE.to_string(), 10),
+            (Burned, String::new(), RUNE.to_string(), 10),
+        ]);
+    }
+
+    #[test]
+    fn test_rune_history() {
+        let id = RuneId { block: HEIGHT, tx: 1 };
+        let etched = BTreeMap::from([(id, Rune(4))]);
+        let blocks = [
+            block(HEIGHT, vec![
+                tx("cb", &[], &[]),
+                runestone_tx("a", &[], &[2, 1, 4, 4, 6, 1_000, 0, 0, 0, 100, 1, 0, 0, 0, 4], 3),
+            ]),
+            block(HEIGHT + 1, vec![
+                tx("cb 2", &[], &[]),
+                tx("b", &[("a", 2), ("a", 3), ("before", 0)], &[1_000, 1_000]),
+                runestone_tx("c", &[("a", 1)], &[22, 1, 0, HEIGHT as u128, 1, 50, 2], 2),
+            ]),
+        ];
+
+        // Balances followed from block to block
+        let mut utxo_balances = BTreeMap::new();
+        let mut records = HashMap::new();
+        for block in &blocks {
+            let result = apply_runestones(block, &etched, &BTreeMap::new(), |utxo| utxo_balances.get(utxo).cloned());
+            utxo_balances.extend(result.created);
+            records.extend(rune_transactions(block, &etched, &BTreeMap::new()));
+        }
+
+        // The same balances are replayed from the records alone
+        let mut lookups = 0;
+        let mut history = RuneHistory::new(Chain::Mainnet, |txid: &str| {
+            lookups += 1;
+            records.get(txid).cloned()
+        });
+        for (utxo, balances) in &utxo_balances {
+            assert_eq!(history.balances(utxo).map(|held| held.balances), Some(balances.balances.clone()));
+        }
+        assert_eq!(history.balances(&utxo("b", 0)).unwrap().balances, held(&[(id, 600)]).balances);
+        assert_eq!(history.balances(&utxo("b", 1)), None);
+        assert_eq!(history.balances(&utxo("before", 0)), None);
+        assert_eq!(history.balances(&utxo("unknown", 0)), None);
+        drop(history);
+        // Each record is read once
+        assert_eq!(lookups, 5);
+    }
+
+    #[test]
+    fn test_before_first_rune_height() {
+        let block = block(HEIGHT - 1, vec![tx("a", &[("prev", 0)], &[1_000])]);
+        assert_eq!(apply_runestones(&block, &BTreeMap::new(), &BTreeMap::new(), |_| Some(held(&[(RUNE, 100)]))), BlockRuneBalances::default());
+    }
+}
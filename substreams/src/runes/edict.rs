@@ -0,0 +1,25 @@
+use bitcoin::Transaction;
+
+use super::rune_id::RuneId;
+
+/// Transfer of `amount` units of rune `id` to output `output`. An output equal
+/// to the number of outputs of the transaction splits the amount between all
+/// its non-OP_RETURN outputs.
+#[derive(Default, Debug, PartialEq, Copy, Clone, Eq)]
+pub struct Edict {
+  pub id: RuneId,
+  pub amount: u128,
+  pub output: u32,
+}
+
+impl Edict {
+  pub(crate) fn from_integers(tx: &Transaction, id: RuneId, amount: u128, output: u128) -> Option<Self> {
+    let output = u32::try_from(output).ok()?;
+
+    if output > u32::try_from(tx.output.len()).ok()? {
+      return None;
+    }
+
+    Some(Self { id, amount, output })
+  }
+}
@@ -0,0 +1,53 @@
+use super::rune::Rune;
+
+/// Creation of a rune. Runes etched without a name are given a reserved one.
+#[derive(Default, Debug, PartialEq, Copy, Clone, Eq)]
+pub struct Etching {
+  pub divisibility: Option<u8>,
+  pub premine: Option<u128>,
+  pub rune: Option<Rune>,
+  pub spacers: Option<u32>,
+  pub symbol: Option<char>,
+  pub terms: Option<Terms>,
+  pub turbo: bool,
+}
+
+/// Terms of the open mints of a rune. Each mint creates `amount` units, up to
+/// `cap` mints, within the absolute `height` and the etching relative `offset`
+/// windows.
+#[derive(Default, Debug, PartialEq, Copy, Clone, Eq)]
+pub struct Terms {
+  pub amount: Option<u128>,
+  pub cap: Option<u128>,
+  pub height: (Option<u64>, Option<u64>),
+  pub offset: (Option<u64>, Option<u64>),
+}
+
+impl Etching {
+  pub const MAX_DIVISIBILITY: u8 = 38;
+  pub const MAX_SPACERS: u32 = 0b00000111_11111111_11111111_11111111;
+
+  /// Maximum supply of the rune, `None` if it does not fit in a `u128`
+  pub fn supply(&self) -> Option<u128> {
+    let premine = self.premine.unwrap_or_default();
+    let cap = self.terms.and_then(|terms| terms.cap).unwrap_or_default();
+    let amount = self.terms.and_then(|terms| terms.amount).unwrap_or_default();
+    premine.checked_add(cap.checked_mul(amount)?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn supply() {
+    assert_eq!(Etching::default().supply(), Some(0));
+    assert_eq!(Etching { premine: Some(10), ..Default::default() }.supply(), Some(10));
+
+    let terms = |cap, amount| Some(Terms { cap: Some(cap), amount: Some(amount), ..Default::default() });
+    assert_eq!(Etching { premine: Some(1), terms: terms(2, 3), ..Default::default() }.supply(), Some(7));
+    assert_eq!(Etching { terms: terms(2, u128::MAX), ..Default::default() }.supply(), None);
+    assert_eq!(Etching { premine: Some(1), terms: terms(1, u128::MAX), ..Default::default() }.supply(), None);
+  }
+}
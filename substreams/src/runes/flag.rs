@@ -0,0 +1,52 @@
+/// Bits of the `Flags` field of a runestone. Unrecognized flags make the
+/// runestone a cenotaph.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Flag {
+  Etching = 0,
+  Terms = 1,
+  Turbo = 2,
+  #[cfg(test)]
+  Cenotaph = 127,
+}
+
+impl Flag {
+  pub(crate) fn mask(self) -> u128 {
+    1 << self as u128
+  }
+
+  #[cfg(test)]
+  pub(crate) fn set(self, flags: &mut u128) {
+    *flags |= self.mask()
+  }
+
+  /// Clears the flag from `flags`, returning whether it was set
+  pub(crate) fn take(self, flags: &mut u128) -> bool {
+    let mask = self.mask();
+    let set = *flags & mask != 0;
+    *flags &= !mask;
+    set
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn take() {
+    let mut flags = 0b101;
+    assert!(Flag::Etching.take(&mut flags));
+    assert!(!Flag::Terms.take(&mut flags));
+    assert!(Flag::Turbo.take(&mut flags));
+    assert_eq!(flags, 0);
+
+    let mut flags = 0;
+    Flag::Etching.set(&mut flags);
+    Flag::Turbo.set(&mut flags);
+    assert_eq!(flags, 0b101);
+
+    let mut flags = Flag::Cenotaph.mask();
+    assert!(!Flag::Etching.take(&mut flags));
+    assert_ne!(flags, 0);
+  }
+}
@@ -0,0 +1,120 @@
+pub mod edict;
+pub mod etching;
+pub mod flag;
+pub mod rune;
+pub mod rune_id;
+pub mod runestone;
+pub mod tag;
+pub mod varint;
+
+#[cfg(test)]
+pub mod test_utils {
+  use bitcoin::{
+    blockdata::{
+      constants::MAX_SCRIPT_ELEMENT_SIZE,
+      opcodes,
+      script::{self, PushBytes, PushBytesBuf},
+    },
+    ScriptBuf,
+  };
+
+  use super::{flag::Flag, rune_id::RuneId, runestone::Runestone, tag::Tag, varint};
+  use crate::decoded::{test_utils::tx, DecodedTransaction};
+
+  /// LEB128 encoding of `integers`
+  pub fn payload(integers: &[u128]) -> Vec<u8> {
+    let mut payload = Vec::new();
+    for &integer in integers {
+      varint::encode_to_vec(integer, &mut payload);
+    }
+    payload
+  }
+
+  /// `OP_RETURN OP_13` script pushing `payload`
+  pub fn runestone_script(payload: &[u8]) -> ScriptBuf {
+    script::Builder::new()
+      .push_opcode(opcodes::all::OP_RETURN)
+      .push_opcode(Runestone::MAGIC_NUMBER)
+      .push_slice(PushBytesBuf::try_from(payload.to_vec()).unwrap())
+      .into_script()
+  }
+
+  /// Transaction named `name` spending `inputs`, whose output 0 is a runestone made
+  /// of `integers`, followed by `outputs` outputs of 1000 sats
+  pub fn runestone_tx(name: &str, inputs: &[(&str, u32)], integers: &[u128], outputs: usize) -> DecodedTransaction {
+    let mut tx = tx(name, inputs, &[vec![0], vec![1_000; outputs]].concat());
+    tx.raw.output[0].script_pubkey = runestone_script(&payload(integers));
+    tx
+  }
+
+  /// Encodes `runestone` as an `OP_RETURN OP_13` script, the inverse of
+  /// `Runestone::decipher`. Edicts are sorted by rune ID to be delta-encoded.
+  pub fn encipher(runestone: &Runestone) -> ScriptBuf {
+    let mut payload = Vec::new();
+
+    if let Some(etching) = runestone.etching {
+      let mut flags = 0;
+      Flag::Etching.set(&mut flags);
+
+      if etching.terms.is_some() {
+        Flag::Terms.set(&mut flags);
+      }
+
+      if etching.turbo {
+        Flag::Turbo.set(&mut flags);
+      }
+
+      Tag::Flags.encode([flags], &mut payload);
+
+      Tag::Rune.encode_option(etching.rune.map(|rune| rune.0), &mut payload);
+      Tag::Divisibility.encode_option(etching.divisibility, &mut payload);
+      Tag::Spacers.encode_option(etching.spacers, &mut payload);
+      Tag::Symbol.encode_option(etching.symbol, &mut payload);
+      Tag::Premine.encode_option(etching.premine, &mut payload);
+
+      if let Some(terms) = etching.terms {
+        Tag::Amount.encode_option(terms.amount, &mut payload);
+        Tag::Cap.encode_option(terms.cap, &mut payload);
+        Tag::HeightStart.encode_option(terms.height.0, &mut payload);
+        Tag::HeightEnd.encode_option(terms.height.1, &mut payload);
+        Tag::OffsetStart.encode_option(terms.offset.0, &mut payload);
+        Tag::OffsetEnd.encode_option(terms.offset.1, &mut payload);
+      }
+    }
+
+    if let Some(RuneId { block, tx }) = runestone.mint {
+      Tag::Mint.encode([block.into(), tx.into()], &mut payload);
+    }
+
+    Tag::Pointer.encode_option(runestone.pointer, &mut payload);
+
+    if !runestone.edicts.is_empty() {
+      varint::encode_to_vec(Tag::Body.into(), &mut payload);
+
+      let mut edicts = runestone.edicts.clone();
+      edicts.sort_by_key(|edict| edict.id);
+
+      let mut previous = RuneId::default();
+      for edict in edicts {
+        // Sorted edicts never go back
+        let (block, tx) = previous.delta(edict.id).unwrap();
+        varint::encode_to_vec(block, &mut payload);
+        varint::encode_to_vec(tx, &mut payload);
+        varint::encode_to_vec(edict.amount, &mut payload);
+        varint::encode_to_vec(edict.output.into(), &mut payload);
+        previous = edict.id;
+      }
+    }
+
+    let mut builder = script::Builder::new()
+      .push_opcode(opcodes::all::OP_RETURN)
+      .push_opcode(Runestone::MAGIC_NUMBER);
+
+    for chunk in payload.chunks(MAX_SCRIPT_ELEMENT_SIZE) {
+      let push: &PushBytes = chunk.try_into().unwrap();
+      builder = builder.push_slice(push);
+    }
+
+    builder.into_script()
+  }
+}
@@ -0,0 +1,256 @@
+use std::{
+  fmt::{self, Display, Formatter},
+  str::FromStr,
+};
+
+use bitcoin::blockdata::constants::SUBSIDY_HALVING_INTERVAL;
+
+use crate::ord::chain::Chain;
+
+/// Name of a rune, as a modified base-26 integer: `A` is 0, `Z` is 25, `AA` is
+/// 26, and so on
+#[derive(Default, Debug, PartialEq, Copy, Clone, PartialOrd, Ord, Eq, Hash)]
+pub struct Rune(pub u128);
+
+/// First rune of each name length: `STEPS[n]` is the smallest rune whose name
+/// is `n + 1` letters long
+const STEPS: &[u128] = &[
+  0,
+  26,
+  702,
+  18278,
+  475254,
+  12356630,
+  321272406,
+  8353082582,
+  217180147158,
+  5646683826134,
+  146813779479510,
+  3817158266467286,
+  99246114928149462,
+  2580398988131886038,
+  67090373691429037014,
+  1744349715977154962390,
+  45353092615406029022166,
+  1179180408000556754576342,
+  30658690608014475618984918,
+  797125955808376366093607894,
+  20725274851017785518433805270,
+  538857146126462423479278937046,
+  14010285799288023010461252363222,
+  364267430781488598271992561443798,
+  9470953200318703555071806597538774,
+  246244783208286292431866971536008150,
+  6402364363415443603228541259936211926,
+  166461473448801533683942072758341510102,
+];
+
+impl Rune {
+  /// Smallest reserved rune. Names from 27 letters on cannot be etched: they
+  /// are given to the runes etched without a name.
+  pub const RESERVED: u128 = 6402364363415443603228541259936211926;
+
+  /// Smallest rune which can be etched at `height`. Names are 13 letters or
+  /// more until the first rune height, then the minimum length drops by one
+  /// letter every 17,500 blocks, linearly within each step, down to a single
+  /// letter after a full halving interval.
+  pub(crate) fn minimum_at_height(chain: Chain, height: u64) -> Self {
+    let offset = height.saturating_add(1);
+
+    const INTERVAL: u64 = SUBSIDY_HALVING_INTERVAL as u64 / 12;
+
+    let start = chain.first_rune_height();
+    let end = start + u64::from(SUBSIDY_HALVING_INTERVAL);
+
+    if offset < start {
+      return Rune(STEPS[12]);
+    }
+
+    if offset >= end {
+      return Rune(0);
+    }
+
+    let progress = offset.saturating_sub(start);
+
+    let length = 12u64.saturating_sub(progress / INTERVAL) as usize;
+
+    let end = STEPS[length];
+    let start = STEPS[length.saturating_sub(1)];
+
+    let remainder = u128::from(progress % INTERVAL);
+
+    Rune(end - ((end - start) * remainder / u128::from(INTERVAL)))
+  }
+
+  pub(crate) fn is_reserved(self) -> bool {
+    self.0 >= Self::RESERVED
+  }
+
+  /// Name given to the rune etched without a name by transaction `tx` of block
+  /// `block`
+  pub(crate) fn reserved(block: u64, tx: u32) -> Self {
+    Self(Self::RESERVED + (u128::from(block) << 32 | u128::from(tx)))
+  }
+
+  /// Data an input must push in its tapscript to commit to the rune: its
+  /// little-endian bytes, without the trailing zeros
+  pub(crate) fn commitment(self) -> Vec<u8> {
+    let bytes = self.0.to_le_bytes();
+
+    let mut end = bytes.len();
+    while end > 0 && bytes[end - 1] == 0 {
+      end -= 1;
+    }
+
+    bytes[..end].into()
+  }
+}
+
+impl Display for Rune {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    let mut n = self.0;
+    if n == u128::MAX {
+      return write!(f, "BCGDENLQRQWDSLRUGSNLBTMFIJAV");
+    }
+
+    n += 1;
+    let mut symbol = String::new();
+    while n > 0 {
+      symbol.push(char::from(b'A' + ((n - 1) % 26) as u8));
+      n = (n - 1) / 26;
+    }
+
+    for c in symbol.chars().rev() {
+      write!(f, "{c}")?;
+    }
+
+    Ok(())
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+  Character(char),
+  Empty,
+  Range,
+}
+
+impl Display for ParseError {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::Character(c) => write!(f, "invalid character `{c}`"),
+      Self::Empty => write!(f, "empty rune name"),
+      Self::Range => write!(f, "name out of range"),
+    }
+  }
+}
+
+impl std::error::Error for ParseError {}
+
+impl FromStr for Rune {
+  type Err = ParseError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if s.is_empty() {
+      return Err(ParseError::Empty);
+    }
+
+    let mut x = 0u128;
+    for (i, c) in s.chars().enumerate() {
+      if i > 0 {
+        x = x.checked_add(1).ok_or(ParseError::Range)?;
+      }
+      x = x.checked_mul(26).ok_or(ParseError::Range)?;
+      match c {
+        'A'..='Z' => {
+          x = x.checked_add(c as u128 - 'A' as u128).ok_or(ParseError::Range)?;
+        }
+        _ => return Err(ParseError::Character(c)),
+      }
+    }
+    Ok(Rune(x))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn names() {
+    for (n, name) in [
+      (0, "A"),
+      (25, "Z"),
+      (26, "AA"),
+      (27, "AB"),
+      (701, "ZZ"),
+      (702, "AAA"),
+      (u128::MAX - 1, "BCGDENLQRQWDSLRUGSNLBTMFIJAU"),
+      (u128::MAX, "BCGDENLQRQWDSLRUGSNLBTMFIJAV"),
+    ] {
+      assert_eq!(Rune(n).to_string(), name);
+      assert_eq!(name.parse::<Rune>(), Ok(Rune(n)));
+    }
+  }
+
+  #[test]
+  fn steps() {
+    for (i, step) in STEPS.iter().enumerate() {
+      assert_eq!(Rune(*step).to_string(), "A".repeat(i + 1));
+    }
+    assert_eq!(Rune(Rune::RESERVED), Rune(STEPS[26]));
+  }
+
+  #[test]
+  fn minimum_at_height() {
+    const START: u64 = 840_000;
+    const END: u64 = START + SUBSIDY_HALVING_INTERVAL as u64;
+    const INTERVAL: u64 = SUBSIDY_HALVING_INTERVAL as u64 / 12;
+
+    #[track_caller]
+    fn case(height: u64, minimum: &str) {
+      assert_eq!(Rune::minimum_at_height(Chain::Mainnet, height).to_string(), minimum);
+    }
+
+    case(0, "AAAAAAAAAAAAA");
+    case(START / 2, "AAAAAAAAAAAAA");
+    case(START - 2, "AAAAAAAAAAAAA");
+    case(START - 1, "AAAAAAAAAAAAA");
+    case(START, "ZZYZXBRKWXVA");
+    case(START + 1, "ZZXZUDIVTVQA");
+    case(START + INTERVAL - 2, "AABACYIPDCFB");
+    case(START + INTERVAL - 1, "AAAAAAAAAAAA");
+    case(START + INTERVAL * 11 - 1, "AA");
+    case(END - 2, "B");
+    case(END - 1, "A");
+    case(END, "A");
+    case(u64::MAX, "A");
+
+    // Runes are indexed from the genesis block on regtest
+    assert_eq!(Rune::minimum_at_height(Chain::Regtest, 0).to_string(), "ZZYZXBRKWXVA");
+  }
+
+  #[test]
+  fn reserved() {
+    assert!(!Rune(Rune::RESERVED - 1).is_reserved());
+    assert!(Rune(Rune::RESERVED).is_reserved());
+    assert_eq!(Rune::reserved(0, 0), Rune(Rune::RESERVED));
+    assert_eq!(Rune::reserved(1, 2), Rune(Rune::RESERVED + (1 << 32) + 2));
+    assert_eq!(Rune::reserved(0, 0).to_string(), "AAAAAAAAAAAAAAAAAAAAAAAAAAA");
+  }
+
+  #[test]
+  fn commitment() {
+    assert_eq!(Rune(0).commitment(), Vec::<u8>::new());
+    assert_eq!(Rune(1).commitment(), [1]);
+    assert_eq!(Rune(256).commitment(), [0, 1]);
+    assert_eq!(Rune(u128::MAX).commitment(), [0xff; 16]);
+  }
+
+  #[test]
+  fn invalid_names() {
+    assert_eq!("".parse::<Rune>(), Err(ParseError::Empty));
+    assert_eq!("a".parse::<Rune>(), Err(ParseError::Character('a')));
+    assert_eq!("BCGDENLQRQWDSLRUGSNLBTMFIJAW".parse::<Rune>(), Err(ParseError::Range));
+  }
+}
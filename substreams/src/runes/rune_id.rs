@@ -0,0 +1,127 @@
+use std::{
+  fmt::{self, Display, Formatter},
+  num::ParseIntError,
+  str::FromStr,
+};
+
+/// Rune identified by the height of the block of its etching and the index
+/// of the etching transaction in that block
+#[derive(Debug, PartialEq, Copy, Clone, Hash, Eq, Ord, PartialOrd, Default)]
+pub struct RuneId {
+  pub block: u64,
+  pub tx: u32,
+}
+
+impl RuneId {
+  pub fn new(block: u64, tx: u32) -> Option<RuneId> {
+    let id = RuneId { block, tx };
+
+    if id.block == 0 && id.tx > 0 {
+      return None;
+    }
+
+    Some(id)
+  }
+
+  /// Rune ID following `self` by the deltas of an edict. The transaction index
+  /// is relative only within the same block.
+  pub fn next(self, block: u128, tx: u128) -> Option<RuneId> {
+    RuneId::new(
+      self.block.checked_add(block.try_into().ok()?)?,
+      if block == 0 {
+        self.tx.checked_add(tx.try_into().ok()?)?
+      } else {
+        tx.try_into().ok()?
+      },
+    )
+  }
+
+  /// Deltas of an edict of rune `next` following an edict of `self`, the
+  /// inverse of `next`. `None` if `next` comes before `self`.
+  #[cfg(test)]
+  pub fn delta(self, next: RuneId) -> Option<(u128, u128)> {
+    let block = next.block.checked_sub(self.block)?;
+
+    let tx = if block == 0 {
+      next.tx.checked_sub(self.tx)?
+    } else {
+      next.tx
+    };
+
+    Some((block.into(), tx.into()))
+  }
+}
+
+impl Display for RuneId {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "{}:{}", self.block, self.tx)
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+  Separator,
+  Block(ParseIntError),
+  Transaction(ParseIntError),
+}
+
+impl Display for ParseError {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::Separator => write!(f, "missing separator"),
+      Self::Block(err) => write!(f, "invalid block height: {err}"),
+      Self::Transaction(err) => write!(f, "invalid transaction index: {err}"),
+    }
+  }
+}
+
+impl std::error::Error for ParseError {}
+
+impl FromStr for RuneId {
+  type Err = ParseError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (height, index) = s.split_once(':').ok_or(ParseError::Separator)?;
+
+    Ok(Self {
+      block: height.parse().map_err(ParseError::Block)?,
+      tx: index.parse().map_err(ParseError::Transaction)?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn display_and_parse() {
+    let id = RuneId { block: 840000, tx: 1 };
+    assert_eq!(id.to_string(), "840000:1");
+    assert_eq!("840000:1".parse::<RuneId>(), Ok(id));
+    assert_eq!("840000".parse::<RuneId>(), Err(ParseError::Separator));
+    assert!("a:1".parse::<RuneId>().is_err());
+  }
+
+  #[test]
+  fn next() {
+    let id = RuneId { block: 1, tx: 2 };
+    assert_eq!(id.next(0, 0), Some(id));
+    assert_eq!(id.next(0, 3), Some(RuneId { block: 1, tx: 5 }));
+    assert_eq!(id.next(2, 3), Some(RuneId { block: 3, tx: 3 }));
+    assert_eq!(RuneId::default().next(0, 1), None);
+    assert_eq!(id.next(u128::from(u64::MAX), 0), None);
+    assert_eq!(id.next(0, u128::from(u32::MAX)), None);
+  }
+
+  #[test]
+  fn delta() {
+    let id = RuneId { block: 1, tx: 2 };
+    for next in [id, RuneId { block: 1, tx: 5 }, RuneId { block: 3, tx: 0 }] {
+      let (block, tx) = id.delta(next).unwrap();
+      assert_eq!(id.next(block, tx), Some(next));
+    }
+    assert_eq!(id.delta(RuneId { block: 1, tx: 1 }), None);
+    assert_eq!(id.delta(RuneId { block: 0, tx: 5 }), None);
+  }
+}
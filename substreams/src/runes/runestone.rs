@@ -0,0 +1,770 @@
+use std::{
+  collections::{HashMap, VecDeque},
+  fmt::{self, Display, Formatter},
+};
+
+use bitcoin::{
+  blockdata::{opcodes, script::Instruction},
+  Transaction,
+};
+
+use super::{
+  edict::Edict,
+  etching::{Etching, Terms},
+  flag::Flag,
+  rune::Rune,
+  rune_id::RuneId,
+  tag::Tag,
+  varint,
+};
+
+/// Protocol message carried by the first `OP_RETURN OP_13` output of a transaction
+#[derive(Default, Debug, PartialEq, Clone, Eq)]
+pub struct Runestone {
+  pub edicts: Vec<Edict>,
+  pub etching: Option<Etching>,
+  pub mint: Option<RuneId>,
+  pub pointer: Option<u32>,
+}
+
+/// Malformed runestone. Cenotaphs burn the runes of the transaction's inputs,
+/// their etching creates a rune without any supply, and their mint is counted
+/// but burned.
+#[derive(Default, Debug, PartialEq, Copy, Clone, Eq)]
+pub struct Cenotaph {
+  pub etching: Option<Rune>,
+  pub flaw: Option<Flaw>,
+  pub mint: Option<RuneId>,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, PartialEq, Clone, Eq)]
+pub enum Artifact {
+  Cenotaph(Cenotaph),
+  Runestone(Runestone),
+}
+
+impl Artifact {
+  /// Rune the artifact mints. Cenotaphs mint too, but burn the runes minted.
+  pub fn mint(&self) -> Option<RuneId> {
+    match self {
+      Self::Cenotaph(cenotaph) => cenotaph.mint,
+      Self::Runestone(runestone) => runestone.mint,
+    }
+  }
+}
+
+/// Reason a runestone is a cenotaph
+#[derive(Debug, PartialEq, Copy, Clone, Eq)]
+pub enum Flaw {
+  EdictOutput,
+  EdictRuneId,
+  InvalidScript,
+  Opcode,
+  SupplyOverflow,
+  TrailingIntegers,
+  TruncatedField,
+  UnrecognizedEvenTag,
+  UnrecognizedFlag,
+  Varint,
+}
+
+impl Display for Flaw {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::EdictOutput => write!(f, "edict output greater than transaction output count"),
+      Self::EdictRuneId => write!(f, "invalid rune ID in edict"),
+      Self::InvalidScript => write!(f, "invalid script in OP_RETURN"),
+      Self::Opcode => write!(f, "non-pushdata opcode in OP_RETURN"),
+      Self::SupplyOverflow => write!(f, "supply overflows u128"),
+      Self::TrailingIntegers => write!(f, "trailing integers in body"),
+      Self::TruncatedField => write!(f, "field with missing value"),
+      Self::UnrecognizedEvenTag => write!(f, "unrecognized even tag"),
+      Self::UnrecognizedFlag => write!(f, "unrecognized field"),
+      Self::Varint => write!(f, "invalid varint"),
+    }
+  }
+}
+
+enum Payload {
+  Valid(Vec<u8>),
+  Invalid(Flaw),
+}
+
+/// Integers of a runestone, split into its fields and its edicts
+struct Message {
+  flaw: Option<Flaw>,
+  edicts: Vec<Edict>,
+  fields: HashMap<u128, VecDeque<u128>>,
+}
+
+impl Message {
+  /// Fields are tag/value pairs until the `Body` tag, which is followed by the
+  /// edicts as groups of four integers. The rune IDs of the edicts are delta
+  /// encoded from the previous edict.
+  fn from_integers(tx: &Transaction, payload: &[u128]) -> Self {
+    let mut edicts = Vec::new();
+    let mut fields = HashMap::<u128, VecDeque<u128>>::new();
+    let mut flaw = None;
+
+    for i in (0..payload.len()).step_by(2) {
+      let tag = payload[i];
+
+      if Tag::Body == tag {
+        let mut id = RuneId::default();
+        for chunk in payload[i + 1..].chunks(4) {
+          if chunk.len() != 4 {
+            flaw.get_or_insert(Flaw::TrailingIntegers);
+            break;
+          }
+
+          let next = match id.next(chunk[0], chunk[1]) {
+            Some(next) => next,
+            None => {
+              flaw.get_or_insert(Flaw::EdictRuneId);
+              break;
+            }
+          };
+
+          let edict = match Edict::from_integers(tx, next, chunk[2], chunk[3]) {
+            Some(edict) => edict,
+            None => {
+              flaw.get_or_insert(Flaw::EdictOutput);
+              break;
+            }
+          };
+
+          id = next;
+          edicts.push(edict);
+        }
+        break;
+      }
+
+      match payload.get(i + 1) {
+        Some(&value) => fields.entry(tag).or_default().push_back(value),
+        None => {
+          flaw.get_or_insert(Flaw::TruncatedField);
+          break;
+        }
+      }
+    }
+
+    Self { flaw, edicts, fields }
+  }
+}
+
+impl Runestone {
+  pub const MAGIC_NUMBER: opcodes::Opcode = opcodes::all::OP_PUSHNUM_13;
+
+  /// Decodes the runestone of `transaction`, if it has any
+  pub fn decipher(transaction: &Transaction) -> Option<Artifact> {
+    let payload = match Runestone::payload(transaction) {
+      Some(Payload::Valid(payload)) => payload,
+      Some(Payload::Invalid(flaw)) => {
+        return Some(Artifact::Cenotaph(Cenotaph {
+          flaw: Some(flaw),
+          ..Default::default()
+        }));
+      }
+      None => return None,
+    };
+
+    let integers = match Runestone::integers(&payload) {
+      Ok(integers) => integers,
+      Err(_) => {
+        return Some(Artifact::Cenotaph(Cenotaph {
+          flaw: Some(Flaw::Varint),
+          ..Default::default()
+        }));
+      }
+    };
+
+    let Message {
+      mut flaw,
+      edicts,
+      mut fields,
+    } = Message::from_integers(transaction, &integers);
+
+    let mut flags = Tag::Flags
+      .take(&mut fields, |[flags]| Some(flags))
+      .unwrap_or_default();
+
+    // The terms and turbo flags are only recognized along the etching flag
+    let etching = if Flag::Etching.take(&mut flags) {
+      Some(Etching {
+        divisibility: Tag::Divisibility.take(&mut fields, |[divisibility]| {
+          let divisibility = u8::try_from(divisibility).ok()?;
+          (divisibility <= Etching::MAX_DIVISIBILITY).then_some(divisibility)
+        }),
+        premine: Tag::Premine.take(&mut fields, |[premine]| Some(premine)),
+        rune: Tag::Rune.take(&mut fields, |[rune]| Some(Rune(rune))),
+        spacers: Tag::Spacers.take(&mut fields, |[spacers]| {
+          let spacers = u32::try_from(spacers).ok()?;
+          (spacers <= Etching::MAX_SPACERS).then_some(spacers)
+        }),
+        symbol: Tag::Symbol.take(&mut fields, |[symbol]| {
+          char::from_u32(u32::try_from(symbol).ok()?)
+        }),
+        terms: Flag::Terms.take(&mut flags).then(|| Terms {
+          cap: Tag::Cap.take(&mut fields, |[cap]| Some(cap)),
+          height: (
+            Tag::HeightStart.take(&mut fields, |[start_height]| u64::try_from(start_height).ok()),
+            Tag::HeightEnd.take(&mut fields, |[end_height]| u64::try_from(end_height).ok()),
+          ),
+          amount: Tag::Amount.take(&mut fields, |[amount]| Some(amount)),
+          offset: (
+            Tag::OffsetStart.take(&mut fields, |[start_offset]| u64::try_from(start_offset).ok()),
+            Tag::OffsetEnd.take(&mut fields, |[end_offset]| u64::try_from(end_offset).ok()),
+          ),
+        }),
+        turbo: Flag::Turbo.take(&mut flags),
+      })
+    } else {
+      None
+    };
+
+    let mint = Tag::Mint.take(&mut fields, |[block, tx]| {
+      RuneId::new(block.try_into().ok()?, tx.try_into().ok()?)
+    });
+
+    let pointer = Tag::Pointer.take(&mut fields, |[pointer]| {
+      let pointer = u32::try_from(pointer).ok()?;
+      (u64::from(pointer) < u64::try_from(transaction.output.len()).ok()?).then_some(pointer)
+    });
+
+    if etching
+      .map(|etching| etching.supply().is_none())
+      .unwrap_or_default()
+    {
+      flaw.get_or_insert(Flaw::SupplyOverflow);
+    }
+
+    if flags != 0 {
+      flaw.get_or_insert(Flaw::UnrecognizedFlag);
+    }
+
+    if fields.keys().any(|tag| tag % 2 == 0) {
+      flaw.get_or_insert(Flaw::UnrecognizedEvenTag);
+    }
+
+    if let Some(flaw) = flaw {
+      return Some(Artifact::Cenotaph(Cenotaph {
+        flaw: Some(flaw),
+        mint,
+        etching: etching.and_then(|etching| etching.rune),
+      }));
+    }
+
+    Some(Artifact::Runestone(Self {
+      edicts,
+      etching,
+      mint,
+      pointer,
+    }))
+  }
+
+  /// Concatenated data pushes of the first output starting with `OP_RETURN OP_13`
+  fn payload(transaction: &Transaction) -> Option<Payload> {
+    for output in &transaction.output {
+      let mut instructions = output.script_pubkey.instructions();
+
+      if instructions.next() != Some(Ok(Instruction::Op(opcodes::all::OP_RETURN))) {
+        continue;
+      }
+
+      // OP_RETURN scripts may be invalid, errors are ignored until the
+      // protocol identifier is found
+      if instructions.next() != Some(Ok(Instruction::Op(Runestone::MAGIC_NUMBER))) {
+        continue;
+      }
+
+      let mut payload = Vec::new();
+
+      for result in instructions {
+        match result {
+          Ok(Instruction::PushBytes(push)) => payload.extend_from_slice(push.as_bytes()),
+          Ok(Instruction::Op(_)) => return Some(Payload::Invalid(Flaw::Opcode)),
+          Err(_) => return Some(Payload::Invalid(Flaw::InvalidScript)),
+        }
+      }
+
+      return Some(Payload::Valid(payload));
+    }
+
+    None
+  }
+
+  fn integers(payload: &[u8]) -> Result<Vec<u128>, varint::Error> {
+    let mut integers = Vec::new();
+    let mut i = 0;
+
+    while i < payload.len() {
+      let (integer, length) = varint::decode(&payload[i..])?;
+      integers.push(integer);
+      i += length;
+    }
+
+    Ok(integers)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use bitcoin::{
+    absolute::LockTime,
+    blockdata::script::{self, PushBytesBuf},
+    transaction::Version,
+    Amount, ScriptBuf, TxOut,
+  };
+
+  use super::*;
+  use crate::runes::test_utils::{self, payload, runestone_script};
+
+  /// Transaction with `script` as first output, followed by two spendable outputs
+  fn tx(script: ScriptBuf) -> Transaction {
+    let output = |script_pubkey| TxOut {
+      value: Amount::ZERO,
+      script_pubkey,
+    };
+
+    Transaction {
+      version: Version::TWO,
+      lock_time: LockTime::ZERO,
+      input: Vec::new(),
+      output: vec![output(script), output(ScriptBuf::new()), output(ScriptBuf::new())],
+    }
+  }
+
+  fn decipher(integers: &[u128]) -> Artifact {
+    Runestone::decipher(&tx(runestone_script(&payload(integers)))).unwrap()
+  }
+
+  fn cenotaph(flaw: Flaw) -> Artifact {
+    Artifact::Cenotaph(Cenotaph {
+      flaw: Some(flaw),
+      ..Default::default()
+    })
+  }
+
+  #[test]
+  fn no_runestone() {
+    let tx = |script: ScriptBuf| Runestone::decipher(&tx(script));
+
+    assert_eq!(tx(ScriptBuf::new()), None);
+    assert_eq!(
+      tx(script::Builder::new().push_opcode(opcodes::all::OP_RETURN).into_script()),
+      None
+    );
+    // Wrong protocol identifier
+    assert_eq!(
+      tx(script::Builder::new()
+        .push_opcode(opcodes::all::OP_RETURN)
+        .push_opcode(opcodes::all::OP_PUSHNUM_12)
+        .into_script()),
+      None
+    );
+    // Not an OP_RETURN
+    assert_eq!(
+      tx(script::Builder::new().push_opcode(Runestone::MAGIC_NUMBER).into_script()),
+      None
+    );
+  }
+
+  #[test]
+  fn empty_runestone() {
+    let script = script::Builder::new()
+      .push_opcode(opcodes::all::OP_RETURN)
+      .push_opcode(Runestone::MAGIC_NUMBER)
+      .into_script();
+    assert_eq!(
+      Runestone::decipher(&tx(script)),
+      Some(Artifact::Runestone(Runestone::default()))
+    );
+  }
+
+  #[test]
+  fn payload_pushes_are_concatenated() {
+    let integers = payload(&[Tag::Mint.into(), 1, Tag::Mint.into(), 2]);
+    let script = script::Builder::new()
+      .push_opcode(opcodes::all::OP_RETURN)
+      .push_opcode(Runestone::MAGIC_NUMBER)
+      .push_slice(PushBytesBuf::try_from(integers[..1].to_vec()).unwrap())
+      .push_slice(PushBytesBuf::try_from(integers[1..].to_vec()).unwrap())
+      .into_script();
+
+    assert_eq!(
+      Runestone::decipher(&tx(script)),
+      Some(Artifact::Runestone(Runestone {
+        mint: Some(RuneId { block: 1, tx: 2 }),
+        ..Default::default()
+      }))
+    );
+  }
+
+  #[test]
+  fn etching() {
+    assert_eq!(
+      decipher(&[
+        Tag::Flags.into(),
+        Flag::Etching.mask() | Flag::Terms.mask() | Flag::Turbo.mask(),
+        Tag::Rune.into(),
+        4,
+        Tag::Divisibility.into(),
+        2,
+        Tag::Spacers.into(),
+        1,
+        Tag::Symbol.into(),
+        'R'.into(),
+        Tag::Premine.into(),
+        100,
+        Tag::Cap.into(),
+        10,
+        Tag::Amount.into(),
+        5,
+        Tag::HeightStart.into(),
+        840000,
+        Tag::HeightEnd.into(),
+        850000,
+        Tag::OffsetEnd.into(),
+        1000,
+        Tag::Pointer.into(),
+        1,
+      ]),
+      Artifact::Runestone(Runestone {
+        etching: Some(Etching {
+          divisibility: Some(2),
+          premine: Some(100),
+          rune: Some(Rune(4)),
+          spacers: Some(1),
+          symbol: Some('R'),
+          terms: Some(Terms {
+            amount: Some(5),
+            cap: Some(10),
+            height: (Some(840000), Some(850000)),
+            offset: (None, Some(1000)),
+          }),
+          turbo: true,
+        }),
+        pointer: Some(1),
+        ..Default::default()
+      })
+    );
+  }
+
+  #[test]
+  fn etching_fields_require_flags() {
+    // Without the etching flag, odd etching fields are ignored and even ones are unrecognized
+    assert_eq!(
+      decipher(&[Tag::Divisibility.into(), 2]),
+      Artifact::Runestone(Runestone::default())
+    );
+    assert_eq!(decipher(&[Tag::Rune.into(), 4]), cenotaph(Flaw::UnrecognizedEvenTag));
+
+    // Terms fields without the terms flag
+    assert_eq!(
+      decipher(&[Tag::Flags.into(), Flag::Etching.mask(), Tag::Cap.into(), 1]),
+      cenotaph(Flaw::UnrecognizedEvenTag)
+    );
+
+    // Terms and turbo flags without the etching flag
+    assert_eq!(
+      decipher(&[Tag::Flags.into(), Flag::Terms.mask(), Tag::Cap.into(), 1]),
+      cenotaph(Flaw::UnrecognizedFlag)
+    );
+    assert_eq!(
+      decipher(&[Tag::Flags.into(), Flag::Turbo.mask()]),
+      cenotaph(Flaw::UnrecognizedFlag)
+    );
+  }
+
+  #[test]
+  fn invalid_field_values_are_ignored() {
+    assert_eq!(
+      decipher(&[
+        Tag::Flags.into(),
+        Flag::Etching.mask(),
+        Tag::Divisibility.into(),
+        u128::from(Etching::MAX_DIVISIBILITY) + 1,
+        Tag::Symbol.into(),
+        0xD800,
+        Tag::Pointer.into(),
+        3,
+      ]),
+      // The invalid pointer and divisibility are left over, but only the pointer tag is even
+      cenotaph(Flaw::UnrecognizedEvenTag)
+    );
+    assert_eq!(
+      decipher(&[
+        Tag::Flags.into(),
+        Flag::Etching.mask(),
+        Tag::Divisibility.into(),
+        u128::from(Etching::MAX_DIVISIBILITY) + 1,
+        Tag::Symbol.into(),
+        0xD800,
+      ]),
+      Artifact::Runestone(Runestone {
+        etching: Some(Etching::default()),
+        ..Default::default()
+      })
+    );
+  }
+
+  #[test]
+  fn edicts() {
+    assert_eq!(
+      decipher(&[
+        Tag::Mint.into(),
+        840000,
+        Tag::Mint.into(),
+        3,
+        Tag::Body.into(),
+        840000,
+        3,
+        10,
+        1,
+        // Same block, next transaction
+        0,
+        2,
+        20,
+        2,
+        // Later block, absolute transaction index
+        1,
+        1,
+        30,
+        3,
+      ]),
+      Artifact::Runestone(Runestone {
+        mint: Some(RuneId { block: 840000, tx: 3 }),
+        edicts: vec![
+          Edict {
+            id: RuneId { block: 840000, tx: 3 },
+            amount: 10,
+            output: 1,
+          },
+          Edict {
+            id: RuneId { block: 840000, tx: 5 },
+            amount: 20,
+            output: 2,
+          },
+          Edict {
+            id: RuneId { block: 840001, tx: 1 },
+            amount: 30,
+            output: 3,
+          },
+        ],
+        ..Default::default()
+      })
+    );
+  }
+
+  #[test]
+  fn cenotaphs() {
+    assert_eq!(decipher(&[Tag::Body.into(), 1, 1, 1]), cenotaph(Flaw::TrailingIntegers));
+    assert_eq!(decipher(&[Tag::Body.into(), 0, 1, 1, 1]), cenotaph(Flaw::EdictRuneId));
+    assert_eq!(decipher(&[Tag::Body.into(), 1, 1, 1, 4]), cenotaph(Flaw::EdictOutput));
+    assert_eq!(decipher(&[Tag::Mint.into()]), cenotaph(Flaw::TruncatedField));
+    assert_eq!(decipher(&[Tag::Cenotaph.into(), 0]), cenotaph(Flaw::UnrecognizedEvenTag));
+    assert_eq!(
+      decipher(&[Tag::Flags.into(), Flag::Cenotaph.mask()]),
+      cenotaph(Flaw::UnrecognizedFlag)
+    );
+    assert_eq!(
+      decipher(&[
+        Tag::Flags.into(),
+        Flag::Etching.mask() | Flag::Terms.mask(),
+        Tag::Cap.into(),
+        2,
+        Tag::Amount.into(),
+        u128::MAX,
+      ]),
+      cenotaph(Flaw::SupplyOverflow)
+    );
+
+    // Odd tags are ignored
+    assert_eq!(decipher(&[Tag::Nop.into(), 0]), Artifact::Runestone(Runestone::default()));
+
+    let tx = |script| Runestone::decipher(&tx(script)).unwrap();
+    assert_eq!(tx(runestone_script(&[0x80])), cenotaph(Flaw::Varint));
+    assert_eq!(
+      tx(script::Builder::new()
+        .push_opcode(opcodes::all::OP_RETURN)
+        .push_opcode(Runestone::MAGIC_NUMBER)
+        .push_opcode(opcodes::all::OP_VERIFY)
+        .into_script()),
+      cenotaph(Flaw::Opcode)
+    );
+    assert_eq!(
+      tx(ScriptBuf::from_bytes(vec![
+        opcodes::all::OP_RETURN.to_u8(),
+        Runestone::MAGIC_NUMBER.to_u8(),
+        opcodes::all::OP_PUSHBYTES_4.to_u8(),
+      ])),
+      cenotaph(Flaw::InvalidScript)
+    );
+  }
+
+  #[test]
+  fn cenotaph_keeps_etched_rune_and_mint() {
+    assert_eq!(
+      decipher(&[
+        Tag::Flags.into(),
+        Flag::Etching.mask(),
+        Tag::Rune.into(),
+        4,
+        Tag::Mint.into(),
+        1,
+        Tag::Mint.into(),
+        0,
+        Tag::Body.into(),
+        1,
+      ]),
+      Artifact::Cenotaph(Cenotaph {
+        etching: Some(Rune(4)),
+        flaw: Some(Flaw::TrailingIntegers),
+        mint: Some(RuneId { block: 1, tx: 0 }),
+      })
+    );
+  }
+
+  #[test]
+  fn first_runestone_only() {
+    let mut tx = tx(runestone_script(&payload(&[Tag::Mint.into(), 1, Tag::Mint.into(), 0])));
+    tx.output.push(TxOut {
+      value: Amount::ZERO,
+      script_pubkey: runestone_script(&payload(&[Tag::Mint.into()])),
+    });
+
+    assert_eq!(
+      Runestone::decipher(&tx),
+      Some(Artifact::Runestone(Runestone {
+        mint: Some(RuneId { block: 1, tx: 0 }),
+        ..Default::default()
+      }))
+    );
+  }
+
+  fn round_trip(runestone: &Runestone) {
+    assert_eq!(
+      Runestone::decipher(&tx(test_utils::encipher(runestone))),
+      Some(Artifact::Runestone(runestone.clone())),
+    );
+  }
+
+  #[test]
+  fn encipher() {
+    let runestone = Runestone {
+      etching: Some(Etching {
+        rune: Some(Rune(4)),
+        premine: Some(1_000),
+        ..Default::default()
+      }),
+      edicts: vec![
+        Edict { id: RuneId { block: 2, tx: 1 }, amount: 5, output: 2 },
+        Edict { id: RuneId { block: 1, tx: 3 }, amount: 0, output: 3 },
+        Edict { id: RuneId { block: 1, tx: 1 }, amount: 7, output: 1 },
+      ],
+      pointer: Some(1),
+      ..Default::default()
+    };
+
+    // Edicts are delta-encoded in rune ID order
+    assert_eq!(
+      test_utils::encipher(&runestone),
+      runestone_script(&payload(&[
+        Tag::Flags.into(), Flag::Etching.mask(),
+        Tag::Rune.into(), 4,
+        Tag::Premine.into(), 1_000,
+        Tag::Pointer.into(), 1,
+        Tag::Body.into(), 1, 1, 7, 1, 0, 2, 0, 3, 1, 1, 5, 2,
+      ])),
+    );
+
+    let mut edicts = runestone.edicts.clone();
+    edicts.sort_by_key(|edict| edict.id);
+    round_trip(&Runestone { edicts, ..runestone });
+
+    round_trip(&Runestone::default());
+  }
+
+  #[test]
+  fn encipher_splits_long_payloads() {
+    let runestone = Runestone {
+      edicts: (1..100)
+        .map(|block| Edict { id: RuneId { block, tx: 0 }, amount: u128::MAX, output: 0 })
+        .collect(),
+      ..Default::default()
+    };
+
+    let script = test_utils::encipher(&runestone);
+    let pushes = script.instructions().filter(|instruction| matches!(instruction, Ok(Instruction::PushBytes(_))));
+    assert!(pushes.count() > 1);
+    round_trip(&runestone);
+  }
+
+  /// Deterministic xorshift generator, to round-trip many combinations of fields
+  struct Rng(u64);
+
+  impl Rng {
+    fn u64(&mut self) -> u64 {
+      self.0 ^= self.0 << 13;
+      self.0 ^= self.0 >> 7;
+      self.0 ^= self.0 << 17;
+      self.0
+    }
+
+    fn bool(&mut self) -> bool {
+      self.u64() & 1 == 0
+    }
+
+    fn u128(&mut self) -> u128 {
+      u128::from(self.u64()) << (self.u64() % 64)
+    }
+
+    fn option<T>(&mut self, value: impl FnOnce(&mut Self) -> T) -> Option<T> {
+      if self.bool() {
+        Some(value(self))
+      } else {
+        None
+      }
+    }
+
+    fn rune_id(&mut self) -> RuneId {
+      RuneId { block: self.u64() % 1_000 + 1, tx: self.u64() as u32 }
+    }
+  }
+
+  #[test]
+  fn encipher_round_trip() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+    for _ in 0..1_000 {
+      let etching = rng.option(|rng| Etching {
+        divisibility: rng.option(|rng| (rng.u64() % u64::from(Etching::MAX_DIVISIBILITY + 1)) as u8),
+        // Bounded so that the supply fits in a `u128`
+        premine: rng.option(|rng| u128::from(rng.u64())),
+        rune: rng.option(|rng| Rune(rng.u128())),
+        spacers: rng.option(|rng| rng.u64() as u32 & Etching::MAX_SPACERS),
+        symbol: rng.option(|rng| char::from_u32((rng.u64() % 0x11_0000) as u32).unwrap_or('R')),
+        terms: rng.option(|rng| Terms {
+          amount: rng.option(|rng| u128::from(rng.u64())),
+          cap: rng.option(|rng| u128::from(rng.u64() as u32)),
+          height: (rng.option(Rng::u64), rng.option(Rng::u64)),
+          offset: (rng.option(Rng::u64), rng.option(Rng::u64)),
+        }),
+        turbo: rng.bool(),
+      });
+
+      let mut edicts = (0..rng.u64() % 4)
+        .map(|_| Edict { id: rng.rune_id(), amount: rng.u128(), output: (rng.u64() % 4) as u32 })
+        .collect::<Vec<_>>();
+      edicts.sort_by_key(|edict| edict.id);
+
+      round_trip(&Runestone {
+        edicts,
+        etching,
+        mint: rng.option(Rng::rune_id),
+        pointer: rng.option(|rng| (rng.u64() % 3) as u32),
+      });
+    }
+  }
+}
@@ -0,0 +1,116 @@
+use std::collections::{HashMap, VecDeque};
+
+/// Tags of the fields of a runestone. Even tags which are not recognized make
+/// the runestone a cenotaph, odd ones are ignored.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Tag {
+  Body = 0,
+  Flags = 2,
+  Rune = 4,
+  Premine = 6,
+  Cap = 8,
+  Amount = 10,
+  HeightStart = 12,
+  HeightEnd = 14,
+  OffsetStart = 16,
+  OffsetEnd = 18,
+  Mint = 20,
+  Pointer = 22,
+  #[cfg(test)]
+  Cenotaph = 126,
+
+  Divisibility = 1,
+  Spacers = 3,
+  Symbol = 5,
+  #[cfg(test)]
+  Nop = 127,
+}
+
+impl Tag {
+  /// Takes the first `N` values of the field, if it holds that many and `with`
+  /// accepts them. Fields are removed once all their values are taken.
+  pub(crate) fn take<const N: usize, T>(
+    self,
+    fields: &mut HashMap<u128, VecDeque<u128>>,
+    with: impl Fn([u128; N]) -> Option<T>,
+  ) -> Option<T> {
+    let field = fields.get_mut(&self.into())?;
+
+    let mut values: [u128; N] = [0; N];
+
+    for (i, v) in values.iter_mut().enumerate() {
+      *v = *field.get(i)?;
+    }
+
+    let value = with(values)?;
+
+    field.drain(0..N);
+
+    if field.is_empty() {
+      fields.remove(&self.into());
+    }
+
+    Some(value)
+  }
+
+  /// Appends a field with each of `values` to `payload`
+  #[cfg(test)]
+  pub(crate) fn encode<const N: usize>(self, values: [u128; N], payload: &mut Vec<u8>) {
+    for value in values {
+      super::varint::encode_to_vec(self.into(), payload);
+      super::varint::encode_to_vec(value, payload);
+    }
+  }
+
+  #[cfg(test)]
+  pub(crate) fn encode_option<T: Into<u128>>(self, value: Option<T>, payload: &mut Vec<u8>) {
+    if let Some(value) = value {
+      self.encode([value.into()], payload)
+    }
+  }
+}
+
+impl From<Tag> for u128 {
+  fn from(tag: Tag) -> Self {
+    tag as u128
+  }
+}
+
+impl PartialEq<u128> for Tag {
+  fn eq(&self, other: &u128) -> bool {
+    u128::from(*self) == *other
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn take() {
+    let mut fields = HashMap::<u128, VecDeque<u128>>::new();
+
+    assert_eq!(Tag::Flags.take(&mut fields, |[flags]| Some(flags)), None);
+
+    fields.insert(2, vec![3, 4].into());
+
+    // Rejected values are left in place
+    assert_eq!(Tag::Flags.take(&mut fields, |[_]| None::<u128>), None);
+    assert_eq!(Tag::Flags.take(&mut fields, |[a, b, c]| Some((a, b, c))), None);
+    assert_eq!(Tag::Flags.take(&mut fields, |[flags]| Some(flags)), Some(3));
+    assert_eq!(Tag::Flags.take(&mut fields, |[flags]| Some(flags)), Some(4));
+    assert!(fields.is_empty());
+  }
+
+  #[test]
+  fn encode() {
+    let mut payload = Vec::new();
+
+    Tag::Flags.encode([3, 4], &mut payload);
+    assert_eq!(payload, [2, 3, 2, 4]);
+
+    Tag::Rune.encode_option(None::<u128>, &mut payload);
+    Tag::Rune.encode_option(Some(300u128), &mut payload);
+    assert_eq!(payload, [2, 3, 2, 4, 4, 0b1010_1100, 0b0000_0010]);
+  }
+}
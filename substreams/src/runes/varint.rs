@@ -0,0 +1,102 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Maximum length of the LEB128 encoding of a `u128`
+const MAX_LENGTH: usize = 19;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Error {
+  Overlong,
+  Overflow,
+  Unterminated,
+}
+
+impl Display for Error {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::Overlong => write!(f, "too long"),
+      Self::Overflow => write!(f, "overflow"),
+      Self::Unterminated => write!(f, "unterminated"),
+    }
+  }
+}
+
+impl std::error::Error for Error {}
+
+/// Appends the LEB128 encoding of `n` to `v`
+#[cfg(test)]
+pub(crate) fn encode_to_vec(mut n: u128, v: &mut Vec<u8>) {
+  while n >> 7 > 0 {
+    v.push(n.to_le_bytes()[0] | 0b1000_0000);
+    n >>= 7;
+  }
+
+  v.push(n.to_le_bytes()[0]);
+}
+
+/// Decodes the LEB128 integer at the start of `buffer`, along with its length
+pub(crate) fn decode(buffer: &[u8]) -> Result<(u128, usize), Error> {
+  let mut n = 0u128;
+
+  for (i, &byte) in buffer.iter().enumerate() {
+    if i >= MAX_LENGTH {
+      return Err(Error::Overlong);
+    }
+
+    let value = u128::from(byte) & 0b0111_1111;
+
+    if i == MAX_LENGTH - 1 && value & 0b0111_1100 != 0 {
+      return Err(Error::Overflow);
+    }
+
+    n |= value << (7 * i);
+
+    if byte & 0b1000_0000 == 0 {
+      return Ok((n, i + 1));
+    }
+  }
+
+  Err(Error::Unterminated)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn decode_integers() {
+    assert_eq!(decode(&[0]), Ok((0, 1)));
+    assert_eq!(decode(&[0x7f, 0xff]), Ok((127, 1)));
+    assert_eq!(decode(&[0b1010_1100, 0b0000_0010]), Ok((300, 2)));
+
+    let mut max = vec![0xff; 18];
+    max.push(0x03);
+    assert_eq!(decode(&max), Ok((u128::MAX, MAX_LENGTH)));
+  }
+
+  #[test]
+  fn round_trip() {
+    for n in [0, 1, 127, 128, 300, u64::MAX.into(), u128::MAX - 1, u128::MAX]
+      .into_iter()
+      .chain((0..128).map(|i| 1 << i))
+    {
+      let mut encoded = Vec::new();
+      encode_to_vec(n, &mut encoded);
+      assert_eq!(decode(&encoded), Ok((n, encoded.len())));
+    }
+
+    let mut encoded = Vec::new();
+    encode_to_vec(300, &mut encoded);
+    assert_eq!(encoded, [0b1010_1100, 0b0000_0010]);
+  }
+
+  #[test]
+  fn invalid() {
+    assert_eq!(decode(&[]), Err(Error::Unterminated));
+    assert_eq!(decode(&[0x80]), Err(Error::Unterminated));
+    assert_eq!(decode(&[0x80; 20]), Err(Error::Overlong));
+
+    let mut overflow = vec![0xff; 18];
+    overflow.push(0x04);
+    assert_eq!(decode(&overflow), Err(Error::Overflow));
+  }
+}
@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+
+use crate::decoded::DecodedBlock;
+use crate::pb::ordinals::v1::{self as ord_proto, RunestoneFlaw};
+use crate::runes::edict::Edict;
+use crate::runes::etching::Etching;
+use crate::runes::rune::Rune;
+use crate::runes::rune_id::RuneId;
+use crate::runes::runestone::{Artifact, Flaw, Runestone};
+
+/// Runestones of the transactions of `block`, in block order. Runestones of blocks
+/// before the first rune height are ignored. Etchings are valid when their rune is
+/// among the `etched` runes, which also name the runes etched without a name.
+pub fn block_runestones(block: &DecodedBlock, etched: &BTreeMap<RuneId, Rune>) -> Vec<ord_proto::Runestone> {
+    if block.height < block.chain.first_rune_height() {
+        return vec![];
+    }
+
+    block.txs.iter()
+        .filter_map(|tx| {
+            let artifact = Runestone::decipher(&tx.raw)?;
+            let id = RuneId { block: block.height, tx: tx.idx };
+            let etching_proto = |etching: &Etching| etching_proto(id, etching, etched.get(&id));
+
+            let runestone = match artifact {
+                Artifact::Runestone(runestone) => ord_proto::Runestone {
+                    etching: runestone.etching.as_ref().map(etching_proto),
+                    mint: runestone.mint.map(|mint| mint.to_string()),
+                    pointer: runestone.pointer,
+                    edicts: runestone.edicts.iter().map(edict_proto).collect(),
+                    ..Default::default()
+                },
+                Artifact::Cenotaph(cenotaph) => ord_proto::Runestone {
+                    // Cenotaphs only etch the rune name, without any supply
+                    etching: cenotaph.etching.map(|rune| etching_proto(&Etching { rune: Some(rune), ..Default::default() })),
+                    mint: cenotaph.mint.map(|mint| mint.to_string()),
+                    cenotaph: true,
+                    flaw: cenotaph.flaw.map(flaw_proto).unwrap_or(RunestoneFlaw::None) as i32,
+                    ..Default::default()
+                },
+            };
+
+            Some(ord_proto::Runestone {
+                txid: tx.txid.clone(),
+                tx_index: tx.idx,
+                block_height: block.height,
+                ..runestone
+            })
+        })
+        .collect()
+}
+
+fn etching_proto(id: RuneId, etching: &Etching, etched: Option<&Rune>) -> ord_proto::Etching {
+    ord_proto::Etching {
+        rune_id: id.to_string(),
+        rune: etched.or(etching.rune.as_ref()).map(|rune| rune.to_string()),
+        divisibility: etching.divisibility.map(u32::from),
+        premine: etching.premine.map(|premine| premine.to_string()),
+        spacers: etching.spacers,
+        symbol: etching.symbol.map(|symbol| symbol.to_string()),
+        terms: etching.terms.map(|terms| ord_proto::MintTerms {
+            amount: terms.amount.map(|amount| amount.to_string()),
+            cap: terms.cap.map(|cap| cap.to_string()),
+            height_start: terms.height.0,
+            height_end: terms.height.1,
+            offset_start: terms.offset.0,
+            offset_end: terms.offset.1,
+        }),
+        turbo: etching.turbo,
+        valid: etched.is_some(),
+    }
+}
+
+fn edict_proto(edict: &Edict) -> ord_proto::Edict {
+    ord_proto::Edict {
+        rune_id: edict.id.to_string(),
+        amount: edict.amount.to_string(),
+        output: edict.output,
+    }
+}
+
+fn flaw_proto(flaw: Flaw) -> RunestoneFlaw {
+    match flaw {
+        Flaw::EdictOutput => RunestoneFlaw::EdictOutput,
+        Flaw::EdictRuneId => RunestoneFlaw::EdictRuneId,
+        Flaw::InvalidScript => RunestoneFlaw::InvalidScript,
+        Flaw::Opcode => RunestoneFlaw::Opcode,
+        Flaw::SupplyOverflow => RunestoneFlaw::SupplyOverflow,
+        Flaw::TrailingIntegers => RunestoneFlaw::TrailingIntegers,
+        Flaw::TruncatedField => RunestoneFlaw::TruncatedField,
+        Flaw::UnrecognizedEvenTag => RunestoneFlaw::UnrecognizedEvenTag,
+        Flaw::UnrecognizedFlag => RunestoneFlaw::UnrecognizedFlag,
+        Flaw::Varint => RunestoneFlaw::Varint,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoded::test_utils::{block, tx};
+    use crate::ord::chain::Chain;
+    use crate::runes::test_utils::runestone_tx;
+
+    #[test]
+    fn test_block_runestones() {
+        let height = Chain::Mainnet.first_rune_height();
+        let txs = vec![
+            tx("cb", &[], &[]),
+            tx("a", &[("prev", 1)], &[1_000]),
+            // Etches rune `E` with a premine of 1000 to output 1
+            runestone_tx("b", &[("prev", 0)], &[2, 1, 4, 4, 6, 1_000, 22, 1], 1),
+            // Mints 840000:2, with a truncated field
+            runestone_tx("c", &[("prev", 0)], &[20, 840_000, 20, 2, 22], 1),
+        ];
+
+        assert!(block_runestones(&block(height - 1, txs.clone()), &BTreeMap::new()).is_empty());
+
+        let etched = BTreeMap::from([(RuneId { block: height, tx: 2 }, "E".parse().unwrap())]);
+        let runestones = block_runestones(&block(height, txs.clone()), &etched);
+        assert_eq!(runestones.len(), 2);
+
+        assert_eq!(runestones[0].tx_index, 2);
+        assert!(!runestones[0].cenotaph);
+        assert_eq!(runestones[0].pointer, Some(1));
+        assert_eq!(
+            runestones[0].etching,
+            Some(ord_proto::Etching {
+                rune_id: format!("{}:2", height),
+                rune: Some("E".into()),
+                premine: Some("1000".into()),
+                valid: true,
+                ..Default::default()
+            }),
+        );
+
+        assert!(runestones[1].cenotaph);
+        assert_eq!(runestones[1].flaw, RunestoneFlaw::TruncatedField as i32);
+        assert_eq!(runestones[1].mint.as_deref(), Some("840000:2"));
+
+        // Etchings which are not valid keep their name, unnamed valid ones get a reserved name
+        let runestones = block_runestones(&block(height, txs.clone()), &BTreeMap::new());
+        assert_eq!(runestones[0].etching.as_ref().map(|etching| (etching.rune.as_deref(), etching.valid)), Some((Some("E"), false)));
+
+        let mut unnamed = txs;
+        unnamed[2] = runestone_tx("b", &[("prev", 0)], &[2, 1], 1);
+        let reserved = Rune::reserved(height, 2);
+        let runestones = block_runestones(&block(height, unnamed), &BTreeMap::from([(RuneId { block: height, tx: 2 }, reserved)]));
+        assert_eq!(runestones[0].etching.as_ref().and_then(|etching| etching.rune.clone()), Some(reserved.to_string()));
+    }
+}
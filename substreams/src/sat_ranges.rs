@@ -0,0 +1,663 @@
+use std::collections::HashMap;
+
+use crate::pb::ordinals::v1::{self as ord_proto, SatRange, SatRanges, SatSource, SatSources};
+use crate::decoded::DecodedBlock;
+use crate::error::{Diagnostics, Error};
+use crate::sats_utils::{block_starting_sat, subsidy, Sat};
+
+impl SatRange {
+    /// First sat after the range
+    pub fn end(&self) -> u64 {
+        self.start + self.size
+    }
+}
+
+impl SatRanges {
+    /// Total number of sats held in the ranges
+    pub fn value(&self) -> u64 {
+        self.ranges.iter().map(|range| range.size).sum()
+    }
+
+    /// Appends a range, merging it with the last one if they are contiguous
+    pub fn push(&mut self, range: SatRange) {
+        if range.size == 0 {
+            return;
+        }
+
+        match self.ranges.last_mut() {
+            Some(last) if last.end() == range.start => last.size += range.size,
+            _ => self.ranges.push(range),
+        }
+    }
+
+    /// Sat at offset `n` within the ranges, if they hold more than `n` sats
+    pub fn nth(&self, n: u64) -> Option<u64> {
+        let mut remaining = n;
+        for range in &self.ranges {
+            if remaining < range.size {
+                return Some(range.start + remaining);
+            }
+            remaining -= range.size;
+        }
+        None
+    }
+
+    /// Offset of `sat` within the ranges, if they hold it
+    pub fn offset_of(&self, sat: u64) -> Option<u64> {
+        let mut offset = 0;
+        for range in &self.ranges {
+            if range.start <= sat && sat < range.end() {
+                return Some(offset + sat - range.start);
+            }
+            offset += range.size;
+        }
+        None
+    }
+
+    /// Ranges tagged with the rarity of their first sat
+    pub fn with_rarity(&self) -> Vec<SatRange> {
+        self.ranges.iter()
+            .map(|range| SatRange { rarity: Sat(range.start).rarity() as i32, ..range.clone() })
+            .collect()
+    }
+
+    /// Sats of rarity uncommon or better held by the ranges, i.e.: the first sats
+    /// of blocks, in order
+    pub fn rare_sats(&self) -> Vec<u64> {
+        let mut rare_sats = vec![];
+        for range in &self.ranges {
+            let mut height = Sat(range.start).height();
+            if block_starting_sat(height) < range.start {
+                height += 1;
+            }
+            while subsidy(height) > 0 && block_starting_sat(height) < range.end() {
+                rare_sats.push(block_starting_sat(height));
+                height += 1;
+            }
+        }
+        rare_sats
+    }
+
+    pub fn append(&mut self, other: SatRanges) {
+        for range in other.ranges {
+            self.push(range);
+        }
+    }
+
+    /// Removes the first `n` sats of the ranges and returns them. If the ranges
+    /// hold less than `n` sats, all of them are returned.
+    pub fn pop_front(&mut self, n: u64) -> SatRanges {
+        let mut popped = SatRanges::default();
+        let mut remaining = n;
+        let mut consumed = 0;
+
+        for range in self.ranges.iter_mut() {
+            if remaining == 0 {
+                break;
+            }
+
+            if range.size <= remaining {
+                remaining -= range.size;
+                popped.push(range.clone());
+                consumed += 1;
+            } else {
+                popped.push(SatRange { start: range.start, size: remaining, ..Default::default() });
+                range.start += remaining;
+                range.size -= remaining;
+                remaining = 0;
+            }
+        }
+
+        self.ranges.drain(..consumed);
+        popped
+    }
+}
+
+impl SatSources {
+    /// Appends a source, merging it with the last one if they are contiguous
+    pub fn push(&mut self, source: SatSource) {
+        if source.size == 0 {
+            return;
+        }
+
+        match self.sources.last_mut() {
+            Some(last) if last.utxo == source.utxo && last.start + last.size == source.start => {
+                last.size += source.size
+            }
+            _ => self.sources.push(source),
+        }
+    }
+
+    /// Removes the first `n` sats of the sources and returns them. If the sources
+    /// hold less than `n` sats, all of them are returned.
+    pub fn pop_front(&mut self, n: u64) -> SatSources {
+        let mut popped = SatSources::default();
+        let mut remaining = n;
+        let mut consumed = 0;
+
+        for source in self.sources.iter_mut() {
+            if remaining == 0 {
+                break;
+            }
+
+            if source.size <= remaining {
+                remaining -= source.size;
+                popped.push(source.clone());
+                consumed += 1;
+            } else {
+                popped.push(SatSource { utxo: source.utxo.clone(), start: source.start, size: remaining });
+                source.start += remaining;
+                source.size -= remaining;
+                remaining = 0;
+            }
+        }
+
+        self.sources.drain(..consumed);
+        popped
+    }
+}
+
+/// Sat ranges moved by a single transaction
+#[derive(Debug, Default, PartialEq)]
+pub struct TransactionSatRanges {
+    /// Spent UTXOs and the sat ranges they held
+    pub inputs: Vec<(String, SatRanges)>,
+    /// Created UTXOs and the sat ranges assigned to them
+    pub outputs: Vec<(String, SatRanges)>,
+    /// Sats left over once the outputs are filled, paid as fee to the coinbase
+    pub fee: SatRanges,
+}
+
+/// Sat ranges moved by the transactions of a block, in block order
+#[derive(Debug, Default, PartialEq)]
+pub struct BlockSatRanges {
+    pub txs: Vec<TransactionSatRanges>,
+    /// UTXOs spent without known sat ranges
+    pub diagnostics: Diagnostics,
+}
+
+/// Sat ranges as output by `map_block_sat_ranges`, to be replayed once per block
+impl From<BlockSatRanges> for ord_proto::BlockSatRanges {
+    fn from(block_sat_ranges: BlockSatRanges) -> Self {
+        let encode = |utxos: Vec<(String, SatRanges)>| {
+            utxos.into_iter()
+                .map(|(utxo, ranges)| ord_proto::UtxoSatRanges { utxo, ranges: Some(ranges) })
+                .collect()
+        };
+
+        Self {
+            txs: block_sat_ranges.txs.into_iter()
+                .map(|tx| ord_proto::TransactionSatRanges {
+                    inputs: encode(tx.inputs),
+                    outputs: encode(tx.outputs),
+                    fee: Some(tx.fee),
+                })
+                .collect(),
+            diagnostics: block_sat_ranges.diagnostics.0,
+        }
+    }
+}
+
+impl From<ord_proto::BlockSatRanges> for BlockSatRanges {
+    fn from(block_sat_ranges: ord_proto::BlockSatRanges) -> Self {
+        let decode = |utxos: Vec<ord_proto::UtxoSatRanges>| {
+            utxos.into_iter()
+                .map(|utxo| (utxo.utxo, utxo.ranges.unwrap_or_default()))
+                .collect()
+        };
+
+        Self {
+            txs: block_sat_ranges.txs.into_iter()
+                .map(|tx| TransactionSatRanges {
+                    inputs: decode(tx.inputs),
+                    outputs: decode(tx.outputs),
+                    fee: tx.fee.unwrap_or_default(),
+                })
+                .collect(),
+            diagnostics: Diagnostics(block_sat_ranges.diagnostics),
+        }
+    }
+}
+
+pub fn utxo_id(txid: &str, vout: u32) -> String {
+    txid.to_string() + ":" + &vout.to_string()
+}
+
+/// Sat ranges as comma separated `start:size` pairs
+pub fn format_sat_ranges(ranges: &[SatRange]) -> String {
+    ranges.iter()
+        .map(|range| format!("{}:{}", range.start, range.size))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Sat ranges paid to the coinbase outputs of the block at `height`: the subsidy sats
+/// first, followed by the fee sats of each transaction in block order. Sats which the
+/// coinbase outputs do not claim are lost.
+pub fn coinbase_sat_ranges(height: u64, fees: &[&SatRanges]) -> SatRanges {
+    let mut ranges = SatRanges::default();
+    ranges.push(SatRange {
+        start: block_starting_sat(height),
+        size: subsidy(height),
+        ..Default::default()
+    });
+
+    for fee in fees {
+        ranges.append((*fee).clone());
+    }
+
+    ranges
+}
+
+/// Assigns absolute sat ranges to the outputs of `block` following ordinal theory.
+/// The sats of a transaction's inputs are popped first-in-first-out into its outputs
+/// and the leftover sats are paid as fees. The coinbase outputs receive the subsidy
+/// sats first, followed by the fees of each transaction in block order.
+///
+/// `lookup` returns the sat ranges of UTXOs created in previous blocks. UTXOs
+/// created and spent within the block are resolved locally. UTXOs which cannot be
+/// resolved are reported and assumed to hold no sat.
+pub fn assign_sat_ranges<F>(block: &DecodedBlock, mut lookup: F) -> BlockSatRanges
+where
+    F: FnMut(&str) -> Option<SatRanges>,
+{
+    let mut created: HashMap<String, SatRanges> = HashMap::new();
+    let mut diagnostics = Diagnostics::default();
+    let mut txs = vec![];
+
+    for tx in block.txs.iter().skip(1) {
+        let mut input_ranges = SatRanges::default();
+        let inputs = tx.inputs()
+            .map(|utxo| {
+                let ranges = created.remove(&utxo)
+                    .or_else(|| lookup(&utxo))
+                    .unwrap_or_else(|| {
+                        diagnostics.report(Error::MissingSatRanges { txid: tx.txid.clone(), utxo: utxo.clone() });
+                        SatRanges::default()
+                    });
+                input_ranges.append(ranges.clone());
+                (utxo, ranges)
+            })
+            .collect();
+
+        let outputs = tx.output_values()
+            .enumerate()
+            .map(|(vout, value)| {
+                let utxo = tx.utxo(vout);
+                let ranges = input_ranges.pop_front(value);
+                created.insert(utxo.clone(), ranges.clone());
+                (utxo, ranges)
+            })
+            .collect();
+
+        txs.push(TransactionSatRanges {
+            inputs,
+            outputs,
+            fee: input_ranges,
+        });
+    }
+
+    // Coinbase transaction, missing from empty blocks
+    if let Some(coinbase_tx) = block.txs.first() {
+        let fees = txs.iter().map(|tx| &tx.fee).collect::<Vec<_>>();
+        let mut coinbase_ranges = coinbase_sat_ranges(block.height, &fees);
+
+        let coinbase_outputs = coinbase_tx.output_values()
+            .enumerate()
+            .map(|(vout, value)| (coinbase_tx.utxo(vout), coinbase_ranges.pop_front(value)))
+            .collect();
+
+        txs.insert(0, TransactionSatRanges {
+            inputs: vec![],
+            outputs: coinbase_outputs,
+            fee: SatRanges::default(),
+        });
+    }
+
+    BlockSatRanges { txs, diagnostics }
+}
+
+/// Sources of the sats assigned to the outputs of `block`, in the same order as
+/// `assign_sat_ranges` assigns them. Outputs only refer to the UTXOs they spend, or
+/// to the subsidy range for the coinbase, so their sat ranges can be resolved later
+/// with `resolve_sat_ranges` without knowing the sat ranges of the spent UTXOs.
+///
+/// `lookup` returns the value of UTXOs created in previous blocks. UTXOs which
+/// cannot be resolved are assumed to hold no sat.
+pub fn assign_sat_sources<F>(block: &DecodedBlock, mut lookup: F) -> Vec<(String, SatSources)>
+where
+    F: FnMut(&str) -> Option<u64>,
+{
+    let mut created: HashMap<String, u64> = HashMap::new();
+    let mut outputs = vec![];
+    let mut fees = vec![];
+
+    for tx in block.txs.iter().skip(1) {
+        let mut input_sources = SatSources::default();
+        for utxo in tx.inputs() {
+            let size = created.remove(&utxo).or_else(|| lookup(&utxo)).unwrap_or_default();
+            input_sources.push(SatSource { utxo, start: 0, size });
+        }
+
+        for (vout, value) in tx.output_values().enumerate() {
+            created.insert(tx.utxo(vout), value);
+            outputs.push((tx.utxo(vout), input_sources.pop_front(value)));
+        }
+
+        fees.push(input_sources);
+    }
+
+    // Coinbase transaction, missing from empty blocks
+    if let Some(coinbase_tx) = block.txs.first() {
+        let mut coinbase_sources = SatSources::default();
+        coinbase_sources.push(SatSource {
+            utxo: String::new(),
+            start: block_starting_sat(block.height),
+            size: subsidy(block.height),
+        });
+        for source in fees.into_iter().flat_map(|fee| fee.sources) {
+            coinbase_sources.push(source);
+        }
+
+        for (vout, value) in coinbase_tx.output_values().enumerate() {
+            outputs.push((coinbase_tx.utxo(vout), coinbase_sources.pop_front(value)));
+        }
+    }
+
+    outputs
+}
+
+/// Sat ranges of `utxo`, following its sources back to the mined sat ranges.
+/// `sources` returns the sources of a UTXO as assigned by `assign_sat_sources`.
+/// Returns `None` if the sources of `utxo` are unknown. Sources taken from UTXOs
+/// with unknown sources are assumed to hold no sat.
+pub fn resolve_sat_ranges<F>(utxo: &str, mut sources: F) -> Option<SatRanges>
+where
+    F: FnMut(&str) -> Option<SatSources>,
+{
+    let mut ranges = SatRanges::default();
+    // Sources left to resolve, the next one last
+    let mut pending: Vec<SatSource> = sources(utxo)?.sources.into_iter().rev().collect();
+
+    while let Some(source) = pending.pop() {
+        if source.utxo.is_empty() {
+            ranges.push(SatRange { start: source.start, size: source.size, ..Default::default() });
+            continue;
+        }
+
+        // Only the sats taken from the spent UTXO are followed
+        let mut spent_sources = sources(&source.utxo).unwrap_or_default();
+        spent_sources.pop_front(source.start);
+        pending.extend(spent_sources.pop_front(source.size).sources.into_iter().rev());
+    }
+
+    Some(ranges)
+}
+
+#[cfg(test)]
+pub mod test_utils {
+    use super::*;
+
+    /// Sat ranges made of `(start, size)` pairs, in order
+    pub fn ranges(ranges: &[(u64, u64)]) -> SatRanges {
+        SatRanges {
+            ranges: ranges.iter()
+                .map(|&(start, size)| SatRange { start, size, ..Default::default() })
+                .collect(),
+        }
+    }
+
+    /// UTXOs and the `(start, size)` sat ranges they hold, in order
+    pub fn utxos(utxos: &[(&str, &[(u64, u64)])]) -> Vec<(String, SatRanges)> {
+        utxos.iter()
+            .map(|&(utxo, sat_ranges)| (utxo.to_string(), ranges(sat_ranges)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::OutPoint;
+
+    use super::test_utils::ranges;
+    use super::*;
+    use crate::decoded::test_utils::{block, tx, utxo};
+    use crate::pb::ordinals::v1::{DiagnosticKind, Rarity};
+
+    #[test]
+    fn test_pop_front() {
+        let mut set = ranges(&[(0, 10), (100, 10)]);
+        assert_eq!(set.pop_front(4), ranges(&[(0, 4)]));
+        assert_eq!(set.pop_front(10), ranges(&[(4, 6), (100, 4)]));
+        assert_eq!(set.pop_front(100), ranges(&[(104, 6)]));
+        assert_eq!(set.pop_front(1), ranges(&[]));
+    }
+
+    #[test]
+    fn test_push_merges_contiguous_ranges() {
+        let mut set = ranges(&[(0, 10)]);
+        set.push(SatRange { start: 10, size: 5, ..Default::default() });
+        set.push(SatRange { start: 20, size: 0, ..Default::default() });
+        set.push(SatRange { start: 30, size: 5, ..Default::default() });
+        assert_eq!(set, ranges(&[(0, 15), (30, 5)]));
+        assert_eq!(set.value(), 20);
+    }
+
+    #[test]
+    fn test_nth() {
+        let set = ranges(&[(0, 10), (100, 10)]);
+        assert_eq!(set.nth(0), Some(0));
+        assert_eq!(set.nth(9), Some(9));
+        assert_eq!(set.nth(10), Some(100));
+        assert_eq!(set.nth(19), Some(109));
+        assert_eq!(set.nth(20), None);
+
+        assert_eq!(set.offset_of(0), Some(0));
+        assert_eq!(set.offset_of(105), Some(15));
+        assert_eq!(set.offset_of(10), None);
+    }
+
+    #[test]
+    fn test_rare_sats() {
+        let set = ranges(&[(1, 10), (4_999_999_999, 2), (10_000_000_000, 10_000_000_001)]);
+        assert_eq!(set.rare_sats(), vec![5_000_000_000, 10_000_000_000, 15_000_000_000, 20_000_000_000]);
+        assert_eq!(ranges(&[(1, 10)]).rare_sats(), vec![]);
+    }
+
+    #[test]
+    fn test_with_rarity() {
+        let set = ranges(&[(0, 10), (1, 5), (5_000_000_000, 10)]);
+        let rarities = set.with_rarity().iter().map(|range| range.rarity).collect::<Vec<_>>();
+        assert_eq!(rarities, vec![Rarity::Mythic as i32, Rarity::Common as i32, Rarity::Uncommon as i32]);
+    }
+
+    #[test]
+    fn test_assign_sat_ranges() {
+        let block = block(1, vec![
+            tx("cb", &[], &[5_000_000_000, 2]),
+            tx("a", &[("prev", 0)], &[3, 4]),
+            tx("b", &[("a", 1), ("prev", 1)], &[6]),
+        ]);
+
+        let previous = HashMap::from([
+            (utxo("prev", 0), ranges(&[(0, 8)])),
+            (utxo("prev", 1), ranges(&[(20, 3)])),
+        ]);
+        let result = assign_sat_ranges(&block, |utxo| previous.get(utxo).cloned());
+
+        assert_eq!(result.txs[1].outputs, vec![
+            (utxo("a", 0), ranges(&[(0, 3)])),
+            (utxo("a", 1), ranges(&[(3, 4)])),
+        ]);
+        assert_eq!(result.txs[1].fee, ranges(&[(7, 1)]));
+
+        // Spends an output created earlier in the same block
+        assert_eq!(result.txs[2].inputs[0], (utxo("a", 1), ranges(&[(3, 4)])));
+        assert_eq!(result.txs[2].outputs, vec![
+            (utxo("b", 0), ranges(&[(3, 4), (20, 2)])),
+        ]);
+        assert_eq!(result.txs[2].fee, ranges(&[(22, 1)]));
+
+        // Subsidy first, then fees in transaction order
+        assert_eq!(result.txs[0].outputs, vec![
+            (utxo("cb", 0), ranges(&[(5_000_000_000, 5_000_000_000)])),
+            (utxo("cb", 1), ranges(&[(7, 1), (22, 1)])),
+        ]);
+
+        // Replayed once by `map_block_sat_ranges`, then shared through its output
+        let output = ord_proto::BlockSatRanges::from(assign_sat_ranges(&block, |utxo| previous.get(utxo).cloned()));
+        assert_eq!(BlockSatRanges::from(output), result);
+    }
+
+    #[test]
+    fn test_coinbase_sat_ranges() {
+        // First sats of the first blocks of each halving epoch on mainnet
+        assert_eq!(coinbase_sat_ranges(0, &[]), ranges(&[(0, 5_000_000_000)]));
+        assert_eq!(coinbase_sat_ranges(210_000, &[]), ranges(&[(1_050_000_000_000_000, 2_500_000_000)]));
+        assert_eq!(coinbase_sat_ranges(420_000, &[]), ranges(&[(1_575_000_000_000_000, 1_250_000_000)]));
+        assert_eq!(coinbase_sat_ranges(630_000, &[]), ranges(&[(1_837_500_000_000_000, 625_000_000)]));
+        assert_eq!(coinbase_sat_ranges(840_000, &[]), ranges(&[(1_968_750_000_000_000, 312_500_000)]));
+
+        let fees = [ranges(&[(42, 1), (7, 2)]), ranges(&[]), ranges(&[(1_000, 10)])];
+        assert_eq!(
+            coinbase_sat_ranges(210_000, &fees.iter().collect::<Vec<_>>()),
+            ranges(&[(1_050_000_000_000_000, 2_500_000_000), (42, 1), (7, 2), (1_000, 10)]),
+        );
+    }
+
+    #[test]
+    fn test_assign_sat_ranges_block_170() {
+        // Mainnet block 170: first bitcoin transaction between two people,
+        // spending the coinbase of block 9 without any fee
+        let block_9_coinbase: OutPoint = "0437cd7f8525ceed2324359c2d0ba26006d92d856a9c20fa0241106ee5a597c9:0"
+            .parse()
+            .unwrap();
+        let mut spend = tx("spend", &[("block 9 coinbase", 0)], &[1_000_000_000, 4_000_000_000]);
+        spend.raw.input[0].previous_output = block_9_coinbase;
+        let block = block(170, vec![tx("cb", &[], &[5_000_000_000]), spend]);
+
+        let result = assign_sat_ranges(&block, |utxo| {
+            assert_eq!(utxo, block_9_coinbase.to_string());
+            Some(coinbase_sat_ranges(9, &[]))
+        });
+
+        assert_eq!(result.txs[0].outputs[0].1, ranges(&[(850_000_000_000, 5_000_000_000)]));
+        assert_eq!(result.txs[1].outputs[0].1, ranges(&[(45_000_000_000, 1_000_000_000)]));
+        assert_eq!(result.txs[1].outputs[1].1, ranges(&[(46_000_000_000, 4_000_000_000)]));
+        assert_eq!(result.txs[1].fee, ranges(&[]));
+    }
+
+    #[test]
+    fn test_assign_sat_ranges_with_fees() {
+        // Two transactions paying fees in the first block of the third halving epoch,
+        // whose coinbase claims the subsidy and part of the fees
+        let height = 420_000;
+        let block = block(height, vec![
+            tx("cb", &[], &[1_250_000_000, 5]),
+            tx("a", &[("prev", 0), ("prev", 1)], &[30, 5]),
+            tx("b", &[("a", 1), ("prev", 2)], &[4]),
+        ]);
+
+        let previous = HashMap::from([
+            (utxo("prev", 0), ranges(&[(1_000, 20)])),
+            (utxo("prev", 1), ranges(&[(50, 5), (2_000, 15)])),
+            (utxo("prev", 2), ranges(&[(3_000, 2)])),
+        ]);
+        let result = assign_sat_ranges(&block, |utxo| previous.get(utxo).cloned());
+
+        assert_eq!(result.txs[1].outputs, vec![
+            (utxo("a", 0), ranges(&[(1_000, 20), (50, 5), (2_000, 5)])),
+            (utxo("a", 1), ranges(&[(2_005, 5)])),
+        ]);
+        assert_eq!(result.txs[1].fee, ranges(&[(2_010, 5)]));
+        assert_eq!(result.txs[2].outputs, vec![(utxo("b", 0), ranges(&[(2_005, 4)]))]);
+        assert_eq!(result.txs[2].fee, ranges(&[(2_009, 1), (3_000, 2)]));
+
+        // The subsidy first, then the fees in transaction order. The coinbase claims 5
+        // of the 8 sats of fees, the last 3 are lost.
+        assert_eq!(result.txs[0].outputs, vec![
+            (utxo("cb", 0), ranges(&[(1_575_000_000_000_000, 1_250_000_000)])),
+            (utxo("cb", 1), ranges(&[(2_010, 5)])),
+        ]);
+    }
+
+    fn sources(sources: &[(&str, u64, u64)]) -> SatSources {
+        SatSources {
+            sources: sources.iter()
+                .map(|&(utxo, start, size)| SatSource { utxo: utxo.to_string(), start, size })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_assign_sat_sources() {
+        let block = block(1, vec![
+            tx("cb", &[], &[5_000_000_000, 2]),
+            tx("a", &[("prev", 0)], &[3, 4]),
+            tx("b", &[("a", 1), ("prev", 1)], &[6]),
+        ]);
+
+        let values = HashMap::from([(utxo("prev", 0), 8), (utxo("prev", 1), 3)]);
+        let result = assign_sat_sources(&block, |utxo| values.get(utxo).copied());
+
+        assert_eq!(result, vec![
+            (utxo("a", 0), sources(&[(&utxo("prev", 0), 0, 3)])),
+            (utxo("a", 1), sources(&[(&utxo("prev", 0), 3, 4)])),
+            (utxo("b", 0), sources(&[(&utxo("a", 1), 0, 4), (&utxo("prev", 1), 0, 2)])),
+            (utxo("cb", 0), sources(&[("", 5_000_000_000, 5_000_000_000)])),
+            (utxo("cb", 1), sources(&[(&utxo("prev", 0), 7, 1), (&utxo("prev", 1), 2, 1)])),
+        ]);
+    }
+
+    #[test]
+    fn test_resolve_sat_ranges() {
+        let blocks = [
+            block(0, vec![tx("prev", &[], &[8, 3, 4_999_999_989])]),
+            block(1, vec![
+                tx("cb", &[], &[5_000_000_000, 2]),
+                tx("a", &[("prev", 0)], &[3, 4]),
+                tx("b", &[("a", 1), ("prev", 1)], &[6]),
+            ]),
+        ];
+
+        // Sat ranges assigned from the sat ranges of the spent UTXOs
+        let mut utxo_sat_ranges = HashMap::new();
+        let mut utxo_sat_sources = HashMap::new();
+        for block in &blocks {
+            let block_sat_ranges = assign_sat_ranges(block, |utxo| utxo_sat_ranges.get(utxo).cloned());
+            assert!(block_sat_ranges.diagnostics.0.is_empty());
+            for tx in block_sat_ranges.txs {
+                utxo_sat_ranges.extend(tx.outputs);
+            }
+
+            let values = utxo_sat_ranges.iter()
+                .map(|(utxo, ranges)| (utxo.clone(), ranges.value()))
+                .collect::<HashMap<_, _>>();
+            utxo_sat_sources.extend(assign_sat_sources(block, |utxo| values.get(utxo).copied()));
+        }
+
+        // The same sat ranges are resolved from the sources alone
+        for (utxo, ranges) in &utxo_sat_ranges {
+            assert_eq!(resolve_sat_ranges(utxo, |utxo| utxo_sat_sources.get(utxo).cloned()).as_ref(), Some(ranges));
+        }
+        assert_eq!(utxo_sat_ranges[&utxo("cb", 1)], ranges(&[(7, 1), (10, 1)]));
+        assert_eq!(resolve_sat_ranges(&utxo("unknown", 0), |utxo| utxo_sat_sources.get(utxo).cloned()), None);
+    }
+
+    #[test]
+    fn test_assign_sat_ranges_missing_utxo() {
+        let block = block(1, vec![
+            tx("cb", &[], &[5_000_000_000]),
+            tx("a", &[("unknown", 0), ("prev", 0)], &[3]),
+        ]);
+
+        let result = assign_sat_ranges(&block, |u| (u == utxo("prev", 0)).then(|| ranges(&[(0, 5)])));
+
+        // The unknown UTXO is reported and assumed to hold no sat
+        assert_eq!(result.txs[1].inputs[0], (utxo("unknown", 0), ranges(&[])));
+        assert_eq!(result.txs[1].outputs[0].1, ranges(&[(0, 3)]));
+        assert_eq!(result.diagnostics.0.len(), 1);
+        assert_eq!(result.diagnostics.0[0].kind, DiagnosticKind::MissingSatRanges as i32);
+        assert_eq!(result.diagnostics.0[0].txid, block.txs[1].txid);
+    }
+}
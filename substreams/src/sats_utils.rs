@@ -1,11 +1,27 @@
-pub fn btc_to_sats(btc_amount: f64) -> u64 {
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Error, Result};
+
+use crate::pb::ordinals::v1::Rarity;
+
+const SUBSIDY_HALVING_INTERVAL: u64 = 210_000;
+const DIFFCHANGE_INTERVAL: u64 = 2016;
+const CYCLE_EPOCHS: u64 = 6;
+/// Epoch from which the subsidy is zero
+const LAST_EPOCH: u64 = 33;
+/// Number of sats that will ever be mined
+pub const SUPPLY: u64 = 2_099_999_997_690_000;
+
+/// Sats in a float BTC amount, if it is a valid one. Only used to check the amounts
+/// reported along the raw transactions, from which exact values are taken.
+pub fn btc_to_sats(btc_amount: f64) -> Option<u64> {
     let s = format!("{:.8}", btc_amount);
-    s.replace(".", "").parse::<u64>().unwrap()
+    s.replace(".", "").parse::<u64>().ok()
 }
 
 // From https://github.com/ordinals/ord/blob/master/bip.mediawiki
 pub fn subsidy(height: u64) -> u64 {
-    50 * 100_000_000 >> (height / 210_000)
+    (50 * 100_000_000) >> (height / 210_000)
 }
 
 pub fn block_supply(height: u64) -> u64 {
@@ -22,13 +38,236 @@ pub fn block_supply(height: u64) -> u64 {
     supply
 }
 
+/// A sat, identified by its ordinal number. Sat arithmetic follows
+/// https://github.com/ordinals/ord/blob/master/bip.mediawiki
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Sat(pub u64);
+
+impl Sat {
+    /// Halving epoch in which the sat was mined
+    pub fn epoch(self) -> u64 {
+        let mut epoch = 0;
+        while epoch < LAST_EPOCH && epoch_starting_sat(epoch + 1) <= self.0 {
+            epoch += 1;
+        }
+        epoch
+    }
+
+    /// Height of the block in which the sat was mined
+    pub fn height(self) -> u64 {
+        let epoch = self.epoch();
+        let subsidy = subsidy(epoch * SUBSIDY_HALVING_INTERVAL);
+        if subsidy == 0 {
+            return epoch * SUBSIDY_HALVING_INTERVAL;
+        }
+        epoch * SUBSIDY_HALVING_INTERVAL + (self.0 - epoch_starting_sat(epoch)) / subsidy
+    }
+
+    /// Offset of the sat within the subsidy of the block in which it was mined
+    pub fn third(self) -> u64 {
+        let epoch = self.epoch();
+        let subsidy = subsidy(epoch * SUBSIDY_HALVING_INTERVAL);
+        if subsidy == 0 {
+            return self.0 - epoch_starting_sat(epoch);
+        }
+        (self.0 - epoch_starting_sat(epoch)) % subsidy
+    }
+
+    /// Difficulty adjustment period in which the sat was mined
+    pub fn period(self) -> u64 {
+        self.height() / DIFFCHANGE_INTERVAL
+    }
+
+    /// Cycle, i.e.: six halving epochs, in which the sat was mined
+    pub fn cycle(self) -> u64 {
+        self.epoch() / CYCLE_EPOCHS
+    }
+
+    /// Rarity of the sat, from the degree notation fields of its block height
+    pub fn rarity(self) -> Rarity {
+        let height = self.height();
+        let hour = height / (CYCLE_EPOCHS * SUBSIDY_HALVING_INTERVAL);
+        let minute = height % SUBSIDY_HALVING_INTERVAL;
+        let second = height % DIFFCHANGE_INTERVAL;
+        let third = self.third();
+
+        if hour == 0 && minute == 0 && second == 0 && third == 0 {
+            Rarity::Mythic
+        } else if minute == 0 && second == 0 && third == 0 {
+            Rarity::Legendary
+        } else if minute == 0 && third == 0 {
+            Rarity::Epic
+        } else if second == 0 && third == 0 {
+            Rarity::Rare
+        } else if third == 0 {
+            Rarity::Uncommon
+        } else {
+            Rarity::Common
+        }
+    }
+
+    /// Degree notation `A°B′C″D‴`: cycle, offset of the block in its halving epoch,
+    /// offset of the block in its difficulty adjustment period and offset of the sat
+    /// in its block
+    pub fn degree(self) -> String {
+        let height = self.height();
+        format!(
+            "{}°{}′{}″{}‴",
+            self.cycle(),
+            height % SUBSIDY_HALVING_INTERVAL,
+            height % DIFFCHANGE_INTERVAL,
+            self.third(),
+        )
+    }
+
+    /// Decimal notation `height.offset`: height of the block in which the sat was
+    /// mined and offset of the sat in that block
+    pub fn decimal(self) -> String {
+        format!("{}.{}", self.height(), self.third())
+    }
+
+    /// Name notation: the sat's distance from the end of the supply in bijective
+    /// base 26, so that the last sats to be mined have the shortest names
+    pub fn name(self) -> String {
+        let mut x = SUPPLY - self.0;
+        let mut name = vec![];
+        while x > 0 {
+            name.push(b'a' + ((x - 1) % 26) as u8);
+            x = (x - 1) / 26;
+        }
+        name.iter().rev().map(|&c| c as char).collect()
+    }
+
+    pub fn from_name(name: &str) -> Result<Sat> {
+        let mut x: u64 = 0;
+        for c in name.chars() {
+            match c {
+                'a'..='z' => {
+                    x = x * 26 + (c as u64 - 'a' as u64 + 1);
+                    if x > SUPPLY {
+                        bail!("sat name out of range: {}", name);
+                    }
+                }
+                _ => bail!("invalid character in sat name: {}", c),
+            }
+        }
+        if x == 0 {
+            bail!("empty sat name");
+        }
+        Ok(Sat(SUPPLY - x))
+    }
+
+    pub fn from_degree(degree: &str) -> Result<Sat> {
+        let (cycle, rest) = degree.split_once('°').context("missing degree symbol")?;
+        let cycle = cycle.parse::<u64>()?;
+
+        let (epoch_offset, rest) = rest.split_once('′').context("missing minute symbol")?;
+        let epoch_offset = epoch_offset.parse::<u64>()?;
+        if epoch_offset >= SUBSIDY_HALVING_INTERVAL {
+            bail!("invalid epoch offset: {}", epoch_offset);
+        }
+
+        let (period_offset, rest) = rest.split_once('″').context("missing second symbol")?;
+        let period_offset = period_offset.parse::<u64>()?;
+        if period_offset >= DIFFCHANGE_INTERVAL {
+            bail!("invalid period offset: {}", period_offset);
+        }
+
+        // Halving epochs are not aligned on difficulty adjustment periods: the period
+        // offset of the first block of an epoch grows by 336 blocks every halving
+        let halving_increment = SUBSIDY_HALVING_INTERVAL % DIFFCHANGE_INTERVAL;
+        let relationship = period_offset + SUBSIDY_HALVING_INTERVAL * CYCLE_EPOCHS - epoch_offset;
+        let epoch_in_cycle = relationship % DIFFCHANGE_INTERVAL / halving_increment;
+        if epoch_in_cycle * halving_increment != relationship % DIFFCHANGE_INTERVAL {
+            bail!("period offset {} does not match epoch offset {}", period_offset, epoch_offset);
+        }
+        let epoch = cycle
+            .checked_mul(CYCLE_EPOCHS)
+            .and_then(|epoch| epoch.checked_add(epoch_in_cycle))
+            .with_context(|| format!("invalid cycle: {}", cycle))?;
+        if epoch >= LAST_EPOCH {
+            bail!("no sats are mined in epoch {}", epoch);
+        }
+        let height = epoch * SUBSIDY_HALVING_INTERVAL + epoch_offset;
+
+        let offset = match rest.split_once('‴') {
+            Some((offset, "")) => offset.parse::<u64>()?,
+            Some(_) => bail!("trailing characters in sat degree: {}", degree),
+            None if rest.is_empty() => 0,
+            None => bail!("missing third symbol"),
+        };
+        if offset >= subsidy(height) {
+            bail!("invalid block offset: {}", offset);
+        }
+
+        Ok(Sat(block_starting_sat(height) + offset))
+    }
+
+    pub fn from_decimal(decimal: &str) -> Result<Sat> {
+        let (height, offset) = decimal.split_once('.').context("missing period")?;
+        let height = height.parse::<u64>()?;
+        let offset = offset.parse::<u64>()?;
+
+        if height >= LAST_EPOCH * SUBSIDY_HALVING_INTERVAL {
+            bail!("no sats are mined at height {}", height);
+        }
+        if offset >= subsidy(height) {
+            bail!("invalid block offset: {}", offset);
+        }
+
+        Ok(Sat(block_starting_sat(height) + offset))
+    }
+}
+
+/// Parses a sat from its integer, name, degree or decimal notation
+impl FromStr for Sat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Sat> {
+        if s.chars().any(|c| c.is_ascii_lowercase()) {
+            Sat::from_name(s)
+        } else if s.contains('°') {
+            Sat::from_degree(s)
+        } else if s.contains('.') {
+            Sat::from_decimal(s)
+        } else {
+            let sat = s.parse::<u64>()?;
+            if sat >= SUPPLY {
+                bail!("sat out of range: {}", sat);
+            }
+            Ok(Sat(sat))
+        }
+    }
+}
+
+/// First sat mined in the block at `height`
+pub fn block_starting_sat(height: u64) -> u64 {
+    if height == 0 {
+        0
+    } else {
+        block_supply(height - 1)
+    }
+}
+
+/// First sat mined in halving epoch `epoch`
+fn epoch_starting_sat(epoch: u64) -> u64 {
+    if epoch == 0 {
+        0
+    } else {
+        block_supply(epoch * SUBSIDY_HALVING_INTERVAL - 1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_btc_to_sats() {
-        assert_eq!(btc_to_sats(0.00000001), 1);
+        assert_eq!(btc_to_sats(0.00000001), Some(1));
+        assert_eq!(btc_to_sats(20999999.9769), Some(2_099_999_997_690_000));
+        assert_eq!(btc_to_sats(-1.), None);
+        assert_eq!(btc_to_sats(f64::NAN), None);
     }
 
     #[test]
@@ -51,4 +290,104 @@ mod tests {
         assert_eq!(block_supply(419999), 1575000000000000, "Block 419999 has 1575000000000000 sats");
         assert_eq!(block_supply(420000), 1575001250000000, "Block 420000 has 1575001250000000 sats");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_sat_height() {
+        assert_eq!(Sat(0).height(), 0);
+        assert_eq!(Sat(4_999_999_999).height(), 0);
+        assert_eq!(Sat(5_000_000_000).height(), 1);
+        assert_eq!(Sat(1_050_000_000_000_000).height(), 210_000);
+        assert_eq!(Sat(1_050_002_500_000_000).height(), 210_001);
+        assert_eq!(Sat(2_067_187_500_000_000).height(), 1_260_000);
+    }
+
+    #[test]
+    fn test_sat_epoch_period_cycle() {
+        assert_eq!(Sat(1_049_999_999_999_999).epoch(), 0);
+        assert_eq!(Sat(1_050_000_000_000_000).epoch(), 1);
+        assert_eq!(Sat(1_968_750_000_000_000).epoch(), 4);
+        assert_eq!(Sat(2_016 * 5_000_000_000 - 1).period(), 0);
+        assert_eq!(Sat(2_016 * 5_000_000_000).period(), 1);
+        assert_eq!(Sat(2_067_187_499_999_999).cycle(), 0);
+        assert_eq!(Sat(2_067_187_500_000_000).cycle(), 1);
+    }
+
+    #[test]
+    fn test_sat_third() {
+        assert_eq!(Sat(0).third(), 0);
+        assert_eq!(Sat(5_000_000_007).third(), 7);
+        assert_eq!(Sat(1_050_002_500_000_001).third(), 1);
+    }
+
+    #[test]
+    fn test_sat_rarity() {
+        assert_eq!(Sat(0).rarity(), Rarity::Mythic);
+        assert_eq!(Sat(1).rarity(), Rarity::Common);
+        assert_eq!(Sat(5_000_000_000).rarity(), Rarity::Uncommon);
+        assert_eq!(Sat(5_000_000_001).rarity(), Rarity::Common);
+        assert_eq!(Sat(2_016 * 5_000_000_000).rarity(), Rarity::Rare);
+        assert_eq!(Sat(1_050_000_000_000_000).rarity(), Rarity::Epic);
+        assert_eq!(Sat(2_067_187_500_000_000).rarity(), Rarity::Legendary);
+    }
+
+    #[test]
+    fn test_supply() {
+        assert_eq!(epoch_starting_sat(LAST_EPOCH), SUPPLY);
+    }
+
+    #[test]
+    fn test_sat_notations() {
+        assert_eq!(Sat(0).name(), "nvtdijuwxlp");
+        assert_eq!(Sat(0).degree(), "0°0′0″0‴");
+        assert_eq!(Sat(0).decimal(), "0.0");
+        assert_eq!(Sat(SUPPLY - 1).name(), "a");
+        assert_eq!(Sat(SUPPLY - 27).name(), "aa");
+        assert_eq!(Sat(5_000_000_001).degree(), "0°1′1″1‴");
+        assert_eq!(Sat(5_000_000_001).decimal(), "1.1");
+        // First block of the second epoch, 336 blocks into its difficulty adjustment period
+        assert_eq!(Sat(1_050_000_000_000_000).degree(), "0°0′336″0‴");
+        assert_eq!(Sat(2_067_187_500_000_000).degree(), "1°0′0″0‴");
+    }
+
+    #[test]
+    fn test_sat_notations_round_trip() {
+        let sats = [
+            0,
+            1,
+            4_999_999_999,
+            5_000_000_000,
+            2_016 * 5_000_000_000,
+            1_050_000_000_000_000,
+            1_234_567_890_123_456,
+            2_067_187_500_000_000,
+            SUPPLY - 1,
+        ];
+        for sat in sats.map(Sat) {
+            assert_eq!(Sat::from_name(&sat.name()).unwrap(), sat);
+            assert_eq!(Sat::from_degree(&sat.degree()).unwrap(), sat);
+            assert_eq!(Sat::from_decimal(&sat.decimal()).unwrap(), sat);
+            assert_eq!(sat.name().parse::<Sat>().unwrap(), sat);
+            assert_eq!(sat.degree().parse::<Sat>().unwrap(), sat);
+            assert_eq!(sat.decimal().parse::<Sat>().unwrap(), sat);
+            assert_eq!(sat.0.to_string().parse::<Sat>().unwrap(), sat);
+        }
+    }
+
+    #[test]
+    fn test_invalid_sat_notations() {
+        assert!(Sat::from_name("").is_err());
+        assert!(Sat::from_name("A").is_err());
+        assert!(Sat::from_name("nvtdijuwxlq").is_err());
+        // Degree without its last fields
+        assert_eq!(Sat::from_degree("0°1′1″").unwrap(), Sat(5_000_000_000));
+        assert!(Sat::from_degree("0°1′1").is_err());
+        assert!(Sat::from_degree("0°1′2″0‴").is_err());
+        assert!(Sat::from_degree("0°210000′0″0‴").is_err());
+        assert!(Sat::from_degree("0°0′0″5000000000‴").is_err());
+        assert!(Sat::from_degree("0°0′0″0‴x").is_err());
+        assert!(Sat::from_degree("18446744073709551615°0′0″0‴").is_err());
+        assert!(Sat::from_decimal("1.5000000000").is_err());
+        assert!(Sat::from_decimal("6930000.0").is_err());
+        assert!(SUPPLY.to_string().parse::<Sat>().is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::BTreeSet;
+
+use crate::mints::parse_terms;
+use crate::pb::ordinals::v1::{Etching, RuneBalanceDelta, RuneBalanceOperation, RuneMint, Runestone, RuneSupply};
+
+/// IDs of the runes whose supply changes in a block: the runes validly etched, the
+/// runes minted and the runes burned
+pub fn updated_runes(runestones: &[Runestone], mints: &[RuneMint], deltas: &[RuneBalanceDelta]) -> BTreeSet<String> {
+    let etched = runestones.iter()
+        .filter_map(|runestone| runestone.etching.as_ref())
+        .filter(|etching| etching.valid)
+        .map(|etching| etching.rune_id.clone());
+    let minted = mints.iter().map(|mint| mint.rune_id.clone());
+    let burned = deltas.iter()
+        .filter(|delta| delta.operation == RuneBalanceOperation::Burned as i32)
+        .map(|delta| delta.rune_id.clone());
+
+    etched.chain(minted).chain(burned).collect()
+}
+
+/// Supply of rune `id` after the block at `height`, from its `etching`, the number of
+/// mints attempted and the amount burned up to the block. Only the first `cap` mints
+/// attempted are accepted.
+pub fn rune_supply(height: u64, id: &str, etching: Option<&Etching>, attempted_mints: u64, burned: u128) -> RuneSupply {
+    let terms = etching.and_then(|etching| etching.terms.as_ref()).and_then(parse_terms);
+    let cap = terms.and_then(|terms| terms.cap).unwrap_or_default();
+    let mint_amount = terms.and_then(|terms| terms.amount).unwrap_or_default();
+    let premine = etching.and_then(|etching| etching.premine.as_deref()).map_or(0, amount);
+
+    let mints = u128::from(attempted_mints).min(cap);
+    let circulating = premine.saturating_add(mint_amount.saturating_mul(mints)).saturating_sub(burned);
+
+    RuneSupply {
+        rune_id: id.to_string(),
+        rune: etching.and_then(|etching| etching.rune.clone()),
+        premine: premine.to_string(),
+        mint_amount: mint_amount.to_string(),
+        mints: mints as u64,
+        burned: burned.to_string(),
+        circulating: circulating.to_string(),
+        block_height: height,
+    }
+}
+
+/// Amount written in base 10, empty amounts being 0
+fn amount(amount: &str) -> u128 {
+    amount.parse().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pb::ordinals::v1::MintTerms;
+
+    fn etching(id: &str, premine: u128, amount: u128, cap: u128, valid: bool) -> Etching {
+        Etching {
+            rune_id: id.into(),
+            rune: Some("RUNE".into()),
+            premine: Some(premine.to_string()),
+            terms: Some(MintTerms { amount: Some(amount.to_string()), cap: Some(cap.to_string()), ..Default::default() }),
+            valid,
+            ..Default::default()
+        }
+    }
+
+    fn burn(id: &str, amount: u128) -> RuneBalanceDelta {
+        RuneBalanceDelta {
+            rune_id: id.into(),
+            amount: amount.to_string(),
+            operation: RuneBalanceOperation::Burned as i32,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_updated_runes() {
+        let runestone = |etching| Runestone { etching: Some(etching), ..Default::default() };
+
+        let updated = updated_runes(
+            &[runestone(etching("840002:1", 100, 1, 1, true)), runestone(etching("840002:2", 100, 1, 1, false))],
+            &[RuneMint { rune_id: "840000:1".into(), ..Default::default() }],
+            &[
+                burn("840000:2", 5),
+                RuneBalanceDelta { operation: RuneBalanceOperation::Received as i32, ..burn("840000:3", 50) },
+            ],
+        );
+
+        assert_eq!(updated, BTreeSet::from(["840000:1".into(), "840000:2".into(), "840002:1".into()]));
+    }
+
+    #[test]
+    fn test_rune_supply() {
+        let etching = etching("840000:1", 1_000, 10, 5, true);
+
+        assert_eq!(rune_supply(840_002, "840000:1", Some(&etching), 3, 25), RuneSupply {
+            rune_id: "840000:1".into(),
+            rune: Some("RUNE".into()),
+            premine: "1000".into(),
+            mint_amount: "10".into(),
+            mints: 3,
+            burned: "25".into(),
+            circulating: "1005".into(),
+            block_height: 840_002,
+        });
+
+        // Mints attempted past the cap are not accepted
+        let supply = rune_supply(840_002, "840000:1", Some(&etching), 7, 0);
+        assert_eq!(supply.mints, 5);
+        assert_eq!(supply.circulating, "1050");
+
+        // Runes etched by a cenotaph do not have any supply
+        let supply = rune_supply(840_002, "840001:1", None, 0, 0);
+        assert_eq!((supply.premine.as_str(), supply.circulating.as_str()), ("0", "0"));
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use bitcoin::TxOut;
+
+use crate::address::address_from_script;
+use crate::decoded::{DecodedBlock, DecodedTransaction};
+use crate::pb::ordinals::v1::{
+    Inscription, InscriptionLocation, InscriptionState, InscriptionTransfer, SatRanges, UtxoInscriptions,
+};
+use crate::sat_ranges::BlockSatRanges;
+
+/// Number of low bits dropped from an inscribed sat to get the bucket it is indexed in
+const INSCRIBED_SAT_BUCKET_BITS: u32 = 28;
+
+/// Inscription moved by a transaction
+struct Move {
+    location: InscriptionLocation,
+    /// UTXO holding the inscription and its address
+    from: (String, Option<String>),
+    /// Whether the inscription is revealed by the transaction
+    revealed: bool,
+}
+
+/// Inscriptions moved by the transactions of a block
+#[derive(Debug, Default, PartialEq)]
+pub struct BlockTransfers {
+    /// Transfers of inscriptions held by the UTXOs spent in the block, in block order,
+    /// along with the inscriptions revealed in a terminal state
+    pub transfers: Vec<InscriptionTransfer>,
+    /// Inscriptions held by the UTXOs created and left unspent by the block
+    pub created: BTreeMap<String, UtxoInscriptions>,
+    /// Inscribed UTXOs created in previous blocks and spent in the block
+    pub spent: Vec<String>,
+}
+
+/// Follows the inscribed sats through the transactions of `block`. Inscriptions
+/// revealed by a transaction (`revealed`) are placed in its outputs without any
+/// transfer, unless they end up in a terminal state, while those held by its inputs
+/// are transferred. Inscribed sats paid as fee are transferred to the coinbase
+/// output receiving them, if any, and are lost otherwise.
+///
+/// `lookup` returns the inscriptions held by UTXOs created in previous blocks.
+/// UTXOs created and spent within the block are resolved locally.
+pub fn transfer_inscriptions<F>(
+    block: &DecodedBlock,
+    block_sat_ranges: &BlockSatRanges,
+    revealed: &[Inscription],
+    mut lookup: F,
+) -> BlockTransfers
+where
+    F: FnMut(&str) -> Option<UtxoInscriptions>,
+{
+    let mut result = BlockTransfers::default();
+
+    let mut revealed_by_tx: HashMap<&str, Vec<&Inscription>> = HashMap::new();
+    for inscription in revealed {
+        if let Some((txid, _)) = inscription.id.rsplit_once('i') {
+            revealed_by_tx.entry(txid).or_default().push(inscription);
+        }
+    }
+
+    // Empty blocks do not move any sat
+    let (coinbase_tx, coinbase_outputs) = match (block.txs.first(), block_sat_ranges.txs.first()) {
+        (Some(tx), Some(tx_sat_ranges)) => (tx, &tx_sat_ranges.outputs),
+        _ => return result,
+    };
+
+    for (tx, tx_sat_ranges) in block.txs.iter().zip(&block_sat_ranges.txs).skip(1) {
+        let mut moves = vec![];
+
+        for (utxo, _) in &tx_sat_ranges.inputs {
+            let utxo_inscriptions = match result.created.remove(utxo) {
+                Some(utxo_inscriptions) => utxo_inscriptions,
+                None => match lookup(utxo) {
+                    Some(utxo_inscriptions) => {
+                        result.spent.push(utxo.clone());
+                        utxo_inscriptions
+                    }
+                    None => continue,
+                },
+            };
+
+            for location in utxo_inscriptions.inscriptions {
+                moves.push(Move {
+                    location,
+                    from: (utxo.clone(), utxo_inscriptions.address.clone()),
+                    revealed: false,
+                });
+            }
+        }
+
+        for inscription in revealed_by_tx.get(tx.txid.as_str()).into_iter().flatten() {
+            // Unbound inscriptions are not made on any sat
+            let sat = match inscription.sat {
+                Some(sat) => sat,
+                None => continue,
+            };
+
+            let input = tx_sat_ranges.inputs.iter().find(|(_, ranges)| ranges.offset_of(sat).is_some());
+            if let Some((utxo, _)) = input {
+                moves.push(Move {
+                    location: InscriptionLocation { id: inscription.id.clone(), sat, offset: 0 },
+                    from: (utxo.clone(), None),
+                    revealed: true,
+                });
+            }
+        }
+
+        for Move { location, from: (from_utxo, from_address), revealed } in moves {
+            let destination = output_holding(tx, &tx_sat_ranges.outputs, location.sat)
+                .map(|(utxo, output, offset)| (utxo, output, offset, output_state(output, InscriptionState::Transferred)))
+                .or_else(|| {
+                    output_holding(coinbase_tx, coinbase_outputs, location.sat)
+                        .map(|(utxo, output, offset)| (utxo, output, offset, output_state(output, InscriptionState::PaidAsFee)))
+                });
+
+            let (to_utxo, to_address, offset, state) = match destination {
+                Some((utxo, output, offset, state)) => (utxo, address_from_script(&output.script_pubkey, block.chain), offset, state),
+                // Sats paid as fee and not claimed by the coinbase are lost
+                None => (String::new(), None, 0, InscriptionState::Lost),
+            };
+
+            if !revealed || state != InscriptionState::Transferred {
+                result.transfers.push(InscriptionTransfer {
+                    inscription_id: location.id.clone(),
+                    from_outpoint: from_utxo,
+                    to_outpoint: to_utxo.clone(),
+                    offset,
+                    from_address,
+                    to_address: to_address.clone(),
+                    txid: tx.txid.clone(),
+                    block_height: block.height,
+                    state: state as i32,
+                });
+            }
+
+            // Terminal inscriptions are not followed anymore
+            if state == InscriptionState::Transferred || state == InscriptionState::PaidAsFee {
+                let utxo_inscriptions = result.created.entry(to_utxo).or_insert_with(|| UtxoInscriptions {
+                    inscriptions: vec![],
+                    address: to_address,
+                });
+                utxo_inscriptions.inscriptions.push(InscriptionLocation { offset, ..location });
+            }
+        }
+    }
+
+    result
+}
+
+/// Bucket `sat` is indexed in once inscribed, so the inscribed sats held by a UTXO
+/// can be found from its sat ranges
+pub fn inscribed_sat_bucket(sat: u64) -> u64 {
+    sat >> INSCRIBED_SAT_BUCKET_BITS
+}
+
+/// Inscriptions held by a UTXO holding `ranges`, in sat order. `inscribed_sats` returns
+/// the inscribed sats indexed in a bucket and `sat_inscriptions` the inscriptions made
+/// on a sat. The address of the UTXO is not known. Returns `None` if the UTXO does not
+/// hold any inscription.
+pub fn utxo_inscriptions<F, G>(
+    ranges: &SatRanges,
+    mut inscribed_sats: F,
+    mut sat_inscriptions: G,
+) -> Option<UtxoInscriptions>
+where
+    F: FnMut(u64) -> Vec<u64>,
+    G: FnMut(u64) -> Vec<String>,
+{
+    let buckets: BTreeSet<u64> = ranges.ranges.iter()
+        .filter(|range| range.size > 0)
+        .flat_map(|range| inscribed_sat_bucket(range.start)..=inscribed_sat_bucket(range.end() - 1))
+        .collect();
+
+    // Inscribed sats held by the UTXO by offset
+    let mut held = BTreeMap::new();
+    for bucket in buckets {
+        for sat in inscribed_sats(bucket) {
+            if let Some(offset) = ranges.offset_of(sat) {
+                held.insert(offset, sat);
+            }
+        }
+    }
+
+    let inscriptions: Vec<_> = held.into_iter()
+        .flat_map(|(offset, sat)| {
+            sat_inscriptions(sat).into_iter().map(move |id| InscriptionLocation { id, sat, offset })
+        })
+        .collect();
+
+    (!inscriptions.is_empty()).then_some(UtxoInscriptions { inscriptions, address: None })
+}
+
+/// UTXO created by `tx` holding `sat`, along with its output and the offset of
+/// the sat within it
+pub fn output_holding<'a>(
+    tx: &'a DecodedTransaction,
+    outputs: &[(String, SatRanges)],
+    sat: u64,
+) -> Option<(String, &'a TxOut, u64)> {
+    tx.raw.output.iter()
+        .zip(outputs)
+        .find_map(|(output, (utxo, ranges))| ranges.offset_of(sat).map(|offset| (utxo.clone(), output, offset)))
+}
+
+/// State of an inscription held by `output`: `spendable` unless the output cannot be spent
+fn output_state(output: &TxOut, spendable: InscriptionState) -> InscriptionState {
+    let script = &output.script_pubkey;
+    if script.is_op_return() {
+        InscriptionState::Burned
+    } else if script.is_provably_unspendable() {
+        InscriptionState::Unspendable
+    } else {
+        spendable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::ScriptBuf;
+
+    use super::*;
+    use crate::decoded::test_utils::{bare_tx, block};
+    use crate::sat_ranges::test_utils::{ranges, utxos};
+    use crate::sat_ranges::TransactionSatRanges;
+
+    fn location(id: &str, sat: u64, offset: u64) -> InscriptionLocation {
+        InscriptionLocation { id: id.into(), sat, offset }
+    }
+
+    #[test]
+    fn test_transfer_inscriptions() {
+        let block = block(800_000, vec![bare_tx("cb", 1), bare_tx("a", 2), bare_tx("b", 1)]);
+        let block_sat_ranges = BlockSatRanges {
+            txs: vec![
+                TransactionSatRanges { outputs: utxos(&[("cb:0", &[(108, 2)])]), ..Default::default() },
+                TransactionSatRanges {
+                    inputs: utxos(&[("prev:0", &[(100, 10)])]),
+                    outputs: utxos(&[("a:0", &[(100, 4)]), ("a:1", &[(104, 6)])]),
+                    ..Default::default()
+                },
+                TransactionSatRanges {
+                    inputs: utxos(&[("a:1", &[(104, 6)]), ("other:0", &[(200, 4)])]),
+                    outputs: utxos(&[("b:0", &[(104, 4)])]),
+                    fee: ranges(&[(108, 2), (200, 4)]),
+                },
+            ],
+            ..Default::default()
+        };
+
+        // Revealed in `b`, paid as fee and not claimed by the coinbase
+        let revealed = vec![Inscription { id: "bi0".into(), sat: Some(202), ..Default::default() }];
+
+        let result = transfer_inscriptions(&block, &block_sat_ranges, &revealed, |utxo| match utxo {
+            "prev:0" => Some(UtxoInscriptions {
+                inscriptions: vec![location("old", 105, 5), location("fee", 109, 9)],
+                address: Some("bc1old".into()),
+            }),
+            _ => None,
+        });
+
+        assert_eq!(result.spent, vec!["prev:0".to_string()]);
+        assert_eq!(
+            result.transfers.iter()
+                .map(|transfer| (
+                    transfer.inscription_id.as_str(),
+                    transfer.from_outpoint.as_str(),
+                    transfer.to_outpoint.as_str(),
+                    transfer.offset,
+                    transfer.txid.as_str(),
+                    transfer.state,
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("old", "prev:0", "a:1", 1, "a", InscriptionState::Transferred as i32),
+                ("fee", "prev:0", "a:1", 5, "a", InscriptionState::Transferred as i32),
+                ("old", "a:1", "b:0", 1, "b", InscriptionState::Transferred as i32),
+                ("fee", "a:1", "cb:0", 1, "b", InscriptionState::PaidAsFee as i32),
+                ("bi0", "other:0", "", 0, "b", InscriptionState::Lost as i32),
+            ],
+        );
+        assert_eq!(result.transfers[0].from_address, Some("bc1old".into()));
+        assert_eq!(result.transfers[0].block_height, 800_000);
+
+        assert_eq!(result.created.keys().collect::<Vec<_>>(), vec!["b:0", "cb:0"]);
+        assert_eq!(result.created["b:0"].inscriptions, vec![location("old", 105, 1)]);
+        assert_eq!(result.created["cb:0"].inscriptions, vec![location("fee", 109, 1)]);
+    }
+
+    #[test]
+    fn test_terminal_states() {
+        let mut reveal = bare_tx("a", 3);
+        // OP_RETURN
+        reveal.raw.output[0].script_pubkey = ScriptBuf::from_bytes(vec![0x6a]);
+        // OP_RESERVED
+        reveal.raw.output[1].script_pubkey = ScriptBuf::from_bytes(vec![0x50]);
+        let block = block(800_000, vec![bare_tx("cb", 1), reveal]);
+        let block_sat_ranges = BlockSatRanges {
+            txs: vec![
+                TransactionSatRanges { outputs: utxos(&[("cb:0", &[(1_000, 10)])]), ..Default::default() },
+                TransactionSatRanges {
+                    inputs: utxos(&[("prev:0", &[(100, 10)])]),
+                    outputs: utxos(&[("a:0", &[(100, 2)]), ("a:1", &[(102, 2)]), ("a:2", &[(104, 6)])]),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let revealed = vec![
+            Inscription { id: "ai0".into(), sat: Some(101), ..Default::default() },
+            Inscription { id: "ai1".into(), sat: Some(105), ..Default::default() },
+        ];
+        let result = transfer_inscriptions(&block, &block_sat_ranges, &revealed, |utxo| match utxo {
+            "prev:0" => Some(UtxoInscriptions { inscriptions: vec![location("old", 103, 3)], address: None }),
+            _ => None,
+        });
+
+        // Inscriptions revealed in a spendable output are not transferred
+        assert_eq!(
+            result.transfers.iter()
+                .map(|transfer| (transfer.inscription_id.as_str(), transfer.to_outpoint.as_str(), transfer.state))
+                .collect::<Vec<_>>(),
+            vec![
+                ("old", "a:1", InscriptionState::Unspendable as i32),
+                ("ai0", "a:0", InscriptionState::Burned as i32),
+            ],
+        );
+
+        // Terminal inscriptions are not followed anymore
+        assert_eq!(result.created.keys().collect::<Vec<_>>(), vec!["a:2"]);
+        assert_eq!(result.created["a:2"].inscriptions, vec![location("ai1", 105, 1)]);
+    }
+
+    #[test]
+    fn test_utxo_inscriptions() {
+        let held = ranges(&[(5 << 28, 10), ((2 << 28) - 2, 4)]);
+        let inscribed_sats = HashMap::from([
+            (1, vec![(2 << 28) - 1, 7]),
+            // Reinscribed sat, indexed once per inscription
+            (2, vec![2 << 28, 2 << 28]),
+            (5, vec![(5 << 28) + 3, (5 << 28) + 10]),
+        ]);
+
+        let mut buckets = vec![];
+        let result = utxo_inscriptions(
+            &held,
+            |bucket| {
+                buckets.push(bucket);
+                inscribed_sats.get(&bucket).cloned().unwrap_or_default()
+            },
+            |sat| match sat {
+                sat if sat == 2 << 28 => vec!["first".into(), "second".into()],
+                sat => vec![sat.to_string()],
+            },
+        );
+
+        assert_eq!(buckets, vec![1, 2, 5]);
+        assert_eq!(result, Some(UtxoInscriptions {
+            inscriptions: vec![
+                location(&((5 << 28) + 3).to_string(), (5 << 28) + 3, 3),
+                location(&((2 << 28) - 1).to_string(), (2 << 28) - 1, 11),
+                location("first", 2 << 28, 12),
+                location("second", 2 << 28, 12),
+            ],
+            address: None,
+        }));
+
+        assert_eq!(utxo_inscriptions(&held, |_| vec![7], |_| vec!["other".into()]), None);
+    }
+}